@@ -0,0 +1,243 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[macro_use]
+extern crate criterion;
+extern crate hazard;
+
+use std::hint;
+use std::thread;
+use std::sync::atomic::Ordering::*;
+use std::time::{Duration, Instant};
+
+use criterion::{BatchSize, Criterion};
+
+use hazard::*;
+
+fn mark(c: &mut Criterion) {
+    let pointers = Pointers::new(BoxMemory, 1, 1, 64);
+    let pointer = AtomicPtr::new(BoxMemory.allocate(0usize));
+
+    c.bench_function("mark/clear", |b| b.iter(|| {
+        hint::black_box(pointers.mark(0, 0, &pointer));
+        pointers.clear(0, 0);
+    }));
+
+    c.bench_function("mark_ptr/clear", |b| b.iter(|| {
+        hint::black_box(pointers.mark_ptr(0, 0, pointer.load(Relaxed)));
+        pointers.clear(0, 0);
+    }));
+
+    unsafe { BoxMemory.deallocate(pointer.load(Relaxed)); }
+}
+
+fn retire(c: &mut Criterion) {
+    for &threshold in &[1, 16, 256] {
+        let pointers = Pointers::new(BoxMemory, 1, 1, threshold);
+        c.bench_function(&format!("retire (threshold = {})", threshold), |b| b.iter(|| {
+            pointers.retire(0, BoxMemory.allocate(0usize));
+        }));
+    }
+}
+
+fn set_threshold(c: &mut Criterion) {
+    let pointers = Pointers::new(BoxMemory, 1, 1, 16);
+    let mut count = 0;
+    c.bench_function("retire (threshold = 16, set every 1024)", |b| b.iter(|| {
+        pointers.retire(0, BoxMemory.allocate(0usize));
+        count += 1;
+        if count % 1024 == 0 {
+            pointers.set_threshold(16);
+        }
+    }));
+}
+
+fn free_order(c: &mut Criterion) {
+    for &order in &[FreeOrder::Fifo, FreeOrder::Lifo] {
+        let pointers = PointersBuilder::new(BoxMemory, 1, 1, 256).free_order(order).build();
+        c.bench_function(&format!("retire (threshold = 256, {:?})", order), |b| b.iter(|| {
+            pointers.retire(0, BoxMemory.allocate([0usize; 8]));
+        }));
+    }
+}
+
+/// Benchmarks how quickly the blocks of a slab are reused under each free order.
+fn slab_reuse(c: &mut Criterion) {
+    for &order in &[FreeOrder::Fifo, FreeOrder::Lifo] {
+        let pointers = PointersBuilder::new(SlabMemory::<[usize; 8]>::new(512), 1, 1, 256)
+            .free_order(order)
+            .build();
+        let name = format!("retire (SlabMemory, threshold = 256, {:?})", order);
+        c.bench_function(&name, |b| b.iter(|| {
+            let pointer = pointers.memory().allocate([0usize; 8]);
+            unsafe { hint::black_box(&mut *pointer)[0] += 1; }
+            pointers.retire(0, pointer);
+        }));
+        pointers.reclaim(0);
+    }
+}
+
+fn retire_remote(c: &mut Criterion) {
+    let pointers = Pointers::new(BoxMemory, 1, 1, 16);
+    let mut count = 0;
+    c.bench_function("retire_remote (reclaimed every 16)", |b| b.iter(|| {
+        pointers.retire_remote(0, BoxMemory.allocate(0usize));
+        count += 1;
+        if count % 16 == 0 {
+            pointers.reclaim(0);
+        }
+    }));
+}
+
+fn reclaim(c: &mut Criterion) {
+    for &threads in &[1, 8, 64] {
+        for &retired in &[16, 256] {
+            let pointers = Pointers::new(BoxMemory, threads, 2, usize::MAX);
+            let hazardous = BoxMemory.allocate(0usize);
+            for thread in 0..threads {
+                pointers.mark_ptr(thread, 0, hazardous);
+            }
+            let name = format!("reclaim (threads = {}, retired = {})", threads, retired);
+            c.bench_function(&name, |b| b.iter_batched(
+                || {
+                    for _ in 0..retired {
+                        pointers.retire(0, BoxMemory.allocate(0usize));
+                    }
+                },
+                |_| hint::black_box(pointers.reclaim(0)),
+                BatchSize::PerIteration,
+            ));
+            for thread in 0..threads {
+                pointers.clear(thread, 0);
+            }
+            unsafe { BoxMemory.deallocate(hazardous); }
+        }
+    }
+}
+
+/// Benchmarks deallocating the reclaimed pointers of a mutex-guarded slab one at a time
+/// (`NaiveScan`) and all at once (`BatchScan`).
+fn reclaim_batch(c: &mut Criterion) {
+    fn bench<R>(c: &mut Criterion, name: &str, reclaimer: R)
+    where R: Reclaimer<usize, SlabMemory<usize>> + 'static {
+        let pointers = PointersBuilder::new(SlabMemory::new(256), 1, 1, usize::MAX)
+            .reclaimer(reclaimer)
+            .build();
+        c.bench_function(name, |b| b.iter_batched(
+            || {
+                for value in 0..256 {
+                    pointers.retire(0, pointers.memory().allocate(value));
+                }
+            },
+            |_| hint::black_box(pointers.reclaim(0)),
+            BatchSize::PerIteration,
+        ));
+    }
+
+    bench(c, "reclaim (SlabMemory, retired = 256, NaiveScan)", NaiveScan);
+    bench(c, "reclaim (SlabMemory, retired = 256, BatchScan)", BatchScan);
+}
+
+fn hazardous(c: &mut Criterion) {
+    for &threads in &[1, 8, 64, 512] {
+        let pointers = Pointers::new(BoxMemory, threads, 4, usize::MAX);
+        let hazardous = BoxMemory.allocate(0usize);
+        let other = BoxMemory.allocate(0usize);
+        for thread in 0..threads {
+            pointers.mark_ptr(thread, 0, hazardous);
+        }
+        c.bench_function(&format!("hazardous (threads = {})", threads), |b| b.iter(|| {
+            hint::black_box(pointers.hazardous(hint::black_box(other)))
+        }));
+        for thread in 0..threads {
+            pointers.clear(thread, 0);
+        }
+        unsafe { BoxMemory.deallocate(hazardous); }
+        unsafe { BoxMemory.deallocate(other); }
+    }
+}
+
+/// Benchmarks the cost of each pointer identity when checking whether a pointer is hazardous.
+fn identity(c: &mut Criterion) {
+    fn bench<I>(c: &mut Criterion, name: &str) where I: PtrIdentity<usize> {
+        let pointers = Pointers::<usize, BoxMemory, I>::with_identity(BoxMemory, 64, 4, usize::MAX);
+        let hazardous = BoxMemory.allocate(0usize);
+        let other = BoxMemory.allocate(0usize);
+        for thread in 0..64 {
+            pointers.mark_ptr(thread, 0, hazardous);
+        }
+        c.bench_function(name, |b| b.iter(|| {
+            hint::black_box(pointers.hazardous(hint::black_box(other)))
+        }));
+        for thread in 0..64 {
+            pointers.clear(thread, 0);
+        }
+        unsafe { BoxMemory.deallocate(hazardous); }
+        unsafe { BoxMemory.deallocate(other); }
+    }
+
+    bench::<Address>(c, "hazardous (threads = 64, Address)");
+    bench::<Exact>(c, "hazardous (threads = 64, Exact)");
+    bench::<Interior>(c, "hazardous (threads = 64, Interior)");
+}
+
+/// A collection shared between the threads of `retired_layout`.
+struct Shared(Pointers<usize, BoxMemory>);
+
+unsafe impl Sync for Shared { }
+
+fn retired_layout(c: &mut Criterion) {
+    for &layout in &[RetiredLayout::PerThread, RetiredLayout::Global, RetiredLayout::Stack] {
+        for &threads in &[1, 2, 4, 8] {
+            let shared = Shared(PointersBuilder::new(BoxMemory, threads, 1, 64)
+                .retired_layout(layout)
+                .build());
+            let name = format!("retire ({:?}, threads = {})", layout, threads);
+            c.bench_function(&name, |b| b.iter_custom(|iterations| {
+                // Each thread retires the supplied number of pointers, so this measures the time
+                // per retire on each thread.
+                let start = Instant::now();
+                thread::scope(|scope| {
+                    for thread in 0..threads {
+                        let shared = &shared;
+                        scope.spawn(move || {
+                            for _ in 0..iterations {
+                                shared.0.retire(thread, BoxMemory.allocate(0usize));
+                            }
+                        });
+                    }
+                });
+                start.elapsed()
+            }));
+        }
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().measurement_time(Duration::from_secs(2));
+    targets =
+        mark,
+        retire,
+        set_threshold,
+        free_order,
+        slab_reuse,
+        retire_remote,
+        reclaim,
+        reclaim_batch,
+        hazardous,
+        identity,
+        retired_layout,
+}
+criterion_main!(benches);
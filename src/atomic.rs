@@ -0,0 +1,222 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Atomic pointers protected by hazardous pointers.
+//!
+//! `AtomicHazardPtr` owns an atomic pointer to a value allocated with the memory of a `Pointers`
+//! and handles marking, validating, clearing, and retiring for the common case of a single shared
+//! value that is replaced as a whole.
+//!
+//! ```
+//! use hazard::{BoxMemory, Pointers};
+//! use hazard::atomic::{AtomicHazardPtr};
+//!
+//! let pointers = Pointers::new(BoxMemory, 1, 1, 2);
+//! let shared = AtomicHazardPtr::new(&pointers, String::from("first"));
+//!
+//! if let Some(value) = unsafe { shared.load_protected(0, 0) } {
+//!     assert_eq!(&*value, "first");
+//! }
+//!
+//! shared.store(0, String::from("second"));
+//! assert_eq!(&*unsafe { shared.load_protected(0, 0) }.unwrap(), "second");
+//! ```
+//!
+//! Since `Pointers` identifies hazardous pointers by index, nothing stops other code from using
+//! the domain of a `HazardRef` while it is live, so `load_protected` is unsafe: the caller must
+//! ensure that the domain is used for nothing else until the `HazardRef` is dropped.
+//!
+//! This does not remove the threading contract of `Pointers`. A `Pointers` is not `Sync` since the
+//! retired pointers of each thread are stored without synchronization, so an `AtomicHazardPtr`
+//! can't be shared between threads on its own either. As with `Pointers`, sharing requires a
+//! wrapper with an `unsafe impl Sync` whose safety argument is that each thread index is only used
+//! by one thread at a time:
+//!
+//! ```
+//! use std::thread;
+//!
+//! use hazard::{BoxMemory, Pointers};
+//! use hazard::atomic::{AtomicHazardPtr};
+//!
+//! struct Shared<'p>(AtomicHazardPtr<'p, usize, BoxMemory>);
+//!
+//! // Safety: thread `i` is only used by the `i`th spawned thread.
+//! unsafe impl<'p> Sync for Shared<'p> { }
+//!
+//! let pointers = Pointers::new(BoxMemory, 4, 1, 8);
+//! let shared = Shared(AtomicHazardPtr::new(&pointers, 0));
+//! thread::scope(|scope| {
+//!     for thread in 0..4 {
+//!         let shared = &shared;
+//!         scope.spawn(move || {
+//!             for value in 0..100 {
+//!                 shared.0.store(thread, value);
+//!                 // Safety: domain `0` of thread `thread` is only used here.
+//!                 assert!(unsafe { shared.0.load_protected(thread, 0) }.is_some());
+//!             }
+//!         });
+//!     }
+//! });
+//! ```
+
+use std::fmt;
+use std::ops;
+use std::ptr;
+use std::sync::atomic::Ordering::*;
+
+use super::{Address, AtomicPtr, Hazard, Memory, Pointers, PtrIdentity};
+
+//================================================
+// Structs
+//================================================
+
+// AtomicHazardPtr _______________________________
+
+/// An atomic pointer whose values are protected and retired using a shared `Pointers`.
+///
+/// The values are allocated and deallocated with the memory of the `Pointers`. The current value
+/// is deallocated when the `AtomicHazardPtr` is dropped.
+pub struct AtomicHazardPtr<'p, T, M, I=Address>
+where T: 'p, M: 'p + Memory, I: 'p + PtrIdentity<T> {
+    pointer: AtomicPtr<T>,
+    pointers: &'p Pointers<T, M, I>,
+}
+
+impl<'p, T, M, I> AtomicHazardPtr<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `AtomicHazardPtr` which points to the supplied value.
+    pub fn new(pointers: &'p Pointers<T, M, I>, value: T) -> Self {
+        let pointer = AtomicPtr::new(pointers.memory.allocate(value));
+        AtomicHazardPtr { pointer, pointers }
+    }
+
+    /// Constructs a new `AtomicHazardPtr` which points to no value.
+    pub fn null(pointers: &'p Pointers<T, M, I>) -> Self {
+        AtomicHazardPtr { pointer: AtomicPtr::new(ptr::null_mut()), pointers }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the current value protected by the supplied domain for the supplied thread, or
+    /// `None` if there is no value.
+    ///
+    /// The value remains protected until the returned reference is dropped, at which point the
+    /// domain is cleared.
+    ///
+    /// # Safety
+    ///
+    /// Until the returned reference is dropped, the supplied domain of the supplied thread must not
+    /// be set or cleared by anything else (e.g., another call to this method, a `Hazard`, or
+    /// `Pointers::clear`), since that would remove the protection of the referenced value.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub unsafe fn load_protected(
+        &self, thread: usize, domain: usize
+    ) -> Option<HazardRef<'_, T, M, I>> {
+        let hazard = self.pointers.protect(thread, domain, &self.pointer);
+        if hazard.as_ptr().is_null() {
+            None
+        } else {
+            Some(HazardRef { hazard })
+        }
+    }
+
+    /// Replaces the current value with the supplied value and retires the previous value using the
+    /// supplied thread.
+    ///
+    /// **Forward progress guarantee:** that of `Pointers::retire`.
+    pub fn store(&self, thread: usize, value: T) {
+        let new = self.pointers.memory.allocate(value);
+        let old = self.pointer.swap(new, AcqRel);
+        if !old.is_null() {
+            self.pointers.retire(thread, old);
+        }
+    }
+
+    /// Replaces the current value with the supplied value if the current value is the supplied
+    /// value and returns whether the value was replaced.
+    ///
+    /// If the value was replaced, the previous value is retired using the supplied thread.
+    /// Otherwise, the supplied value is dropped. The current value should be compared against a
+    /// value loaded with `load_protected` that is still protected.
+    ///
+    /// **Forward progress guarantee:** that of `Pointers::retire`.
+    pub fn compare_and_set(&self, thread: usize, current: *const T, value: T) -> bool {
+        let new = self.pointers.memory.allocate(value);
+        match self.pointer.compare_exchange(current as *mut T, new, AcqRel, Acquire) {
+            Ok(old) => {
+                if !old.is_null() {
+                    self.pointers.retire(thread, old);
+                }
+                true
+            },
+            Err(_) => {
+                unsafe { self.pointers.memory.deallocate(new); }
+                false
+            },
+        }
+    }
+}
+
+impl<'p, T, M, I> Drop for AtomicHazardPtr<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn drop(&mut self) {
+        let pointer = self.pointer.load(Relaxed);
+        if !pointer.is_null() {
+            unsafe { self.pointers.memory.deallocate(pointer); }
+        }
+    }
+}
+
+impl<'p, T, M, I> fmt::Debug for AtomicHazardPtr<'p, T, M, I>
+where M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("AtomicHazardPtr").field("pointer", &self.pointer).finish()
+    }
+}
+
+// HazardRef _____________________________________
+
+/// A reference to a value which is protected by a hazardous pointer until dropped.
+///
+/// See `AtomicHazardPtr::load_protected`.
+#[must_use = "the value is no longer protected once the `HazardRef` is dropped"]
+pub struct HazardRef<'p, T, M, I=Address>
+where T: 'p, M: 'p + Memory, I: 'p + PtrIdentity<T> {
+    hazard: Hazard<'p, T, M, I>,
+}
+
+impl<'p, T, M, I> HazardRef<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Accessors --------------------------------
+
+    /// Returns the protected pointer.
+    pub fn as_ptr(&self) -> *const T {
+        self.hazard.as_ptr()
+    }
+}
+
+impl<'p, T, M, I> ops::Deref for HazardRef<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.hazard.as_ptr() }
+    }
+}
+
+impl<'p, T, M, I> fmt::Debug for HazardRef<'p, T, M, I>
+where T: fmt::Debug, M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("HazardRef").field(&**self).finish()
+    }
+}
@@ -15,138 +15,4666 @@
 //! Hazard pointers.
 //!
 //! * [Hazard Pointers: Safe Memory Reclamation for Lock-Free Objects](http://web.cecs.pdx.edu/~walpole/class/cs510/papers/11.pdf)
+//!
+//! # Address Reuse
+//!
+//! A hazardous pointer protects an address rather than a particular allocation. A pointer is never
+//! deallocated while it is hazardous, so an address cannot be reused while it is protected, but a
+//! protection that failed validation and has not yet been cleared will keep a new allocation at
+//! the same address alive until it is cleared or replaced. This only delays reclamation and never
+//! causes a use-after-free.
+//!
+//! Hazardous pointers do not prevent the ABA problem for compare-and-swap operations on pointers
+//! whose addresses are reused by the `Memory` implementation. Structures which require that must
+//! store a tag or counter alongside their pointers (see `Pointers::mark` for how tagged pointers
+//! are handled and `Pointers::protect_tagged` for pointers packed into a word with a counter) or
+//! detect the reuse of addresses with `AbaGuard`.
+//!
+//! # Usage
+//!
+//! The `atomic` module packages the correct use of hazardous pointers for a single atomic pointer
+//! and is the recommended entry point. The methods of `Pointers` can be used directly to protect
+//! and retire pointers in more complex structures.
+
+#![warn(missing_copy_implementations, missing_debug_implementations, missing_docs)]
+
+#[cfg(loom)]
+extern crate loom;
+#[cfg(feature="portable-atomic")]
+extern crate portable_atomic;
+#[cfg(feature="tracing")]
+extern crate tracing;
+
+pub mod atomic;
+#[cfg(feature="test-util")]
+pub mod stress;
+mod sync;
+
+pub use sync::{AtomicPtr, AtomicUsize};
+
+use std::alloc;
+use std::any;
+use std::array;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::mem::{self, MaybeUninit};
+use std::ops;
+use std::ptr::{self, NonNull};
+use std::thread;
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::collections::{HashMap, HashSet, TryReserveError};
+use std::collections::hash_map::{RandomState};
+use std::hash::{BuildHasher};
+use std::marker::{PhantomData};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::sync::atomic::Ordering::*;
+use std::time::{Duration, Instant};
+#[cfg(feature="async")]
+use std::future::{Future};
+#[cfg(feature="async")]
+use std::pin::{Pin};
+#[cfg(feature="async")]
+use std::task::{Context, Poll};
+
+use sync::{fence};
+
+//================================================
+// Constants
+//================================================
+
+/// The byte that values are overwritten with just before they are reclaimed.
+///
+/// If debug assertions are enabled, a `Pointers` overwrites each value that does not need to be
+/// dropped with this byte just before it is deallocated, so a thread that reads a value it failed
+/// to protect is likely to read obviously corrupt data. Values that need to be dropped are not
+/// poisoned since they are dropped by `Memory::deallocate`.
+pub const POISON: u8 = 0xDE;
+
+//================================================
+// Functions
+//================================================
+
+/// Returns the size of a cacheline in bytes for the current processor.
+///
+/// On Linux, this is read from `sysfs` the first time this function is called. If the size can't
+/// be detected (e.g., on other platforms), `CACHELINE` is returned instead. This is intended for
+/// validating that `CACHELINE` is suitable for the current processor.
+pub fn cacheline_size() -> usize {
+    static SIZE: OnceLock<usize> = OnceLock::new();
+    *SIZE.get_or_init(|| {
+        let path = "/sys/devices/system/cpu/cpu0/cache/index0/coherency_line_size";
+        fs::read_to_string(path).ok()
+            .and_then(|s| s.trim().parse().ok())
+            .filter(|s| *s != 0)
+            .unwrap_or(CACHELINE)
+    })
+}
+
+/// Overwrites the value the supplied pointer points to with `POISON` if debug assertions are
+/// enabled and `T` does not need to be dropped.
+unsafe fn poison<T>(pointer: *mut T) {
+    if cfg!(debug_assertions) && !mem::needs_drop::<T>() {
+        ptr::write_bytes(pointer as *mut u8, POISON, mem::size_of::<T>());
+    }
+}
+
+/// Hints to the processor that the memory the supplied pointer points to will be read soon.
+///
+/// This is a no-op on targets other than `x86_64` with SSE.
+fn prefetch<T>(pointer: *const T) {
+    #[cfg(all(target_arch="x86_64", target_feature="sse"))]
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(pointer as *const i8);
+    }
+    #[cfg(not(all(target_arch="x86_64", target_feature="sse")))]
+    let _ = pointer;
+}
+
+/// Returns a pseudorandom number from a generator local to the current thread.
+fn random() -> u64 {
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(RandomState::new().hash_one(0u64) | 1);
+    }
+
+    STATE.with(|s| {
+        let mut x = s.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        s.set(x);
+        x
+    })
+}
+
+/// Collects the values returned by the supplied function for each index in `0..length` or returns
+/// an error if allocation fails.
+fn try_collect<T, F>(length: usize, f: F) -> Result<Vec<T>, TryReserveError>
+where F: FnMut(usize) -> T {
+    let mut vec = Vec::new();
+    vec.try_reserve_exact(length)?;
+    vec.extend((0..length).map(f));
+    Ok(vec)
+}
+
+//================================================
+// Traits
+//================================================
+
+// HazardView ____________________________________
+
+/// A type-erased view of a collection of hazardous pointers.
+pub trait HazardView {
+    /// Returns whether the supplied address is considered hazardous.
+    fn is_address_hazardous(&self, address: *const ()) -> bool;
+}
+
+// Interchangeable _______________________________
+
+/// A memory whose instances can be compared for whether they can deallocate each other's pointers.
+///
+/// This is required by `Pointers::merge` and `Pointers::split_off`, which move retired pointers
+/// from one collection to another so that they are deallocated with the memory of the other.
+/// References to and `Arc`s of memories are interchangeable if they point to the same memory.
+///
+/// # Safety
+///
+/// `interchangeable` must only return `true` if every pointer allocated by either memory may be
+/// deallocated by the other.
+pub unsafe trait Interchangeable: Memory {
+    /// Returns whether pointers allocated by this memory may be deallocated by the supplied memory
+    /// and vice versa.
+    fn interchangeable(&self, other: &Self) -> bool;
+}
+
+unsafe impl<M> Interchangeable for &M where M: Memory {
+    fn interchangeable(&self, other: &Self) -> bool {
+        ptr::eq(*self, *other)
+    }
+}
+
+unsafe impl<M> Interchangeable for Arc<M> where M: Memory {
+    fn interchangeable(&self, other: &Self) -> bool {
+        Arc::ptr_eq(self, other)
+    }
+}
+
+// LayoutMemory __________________________________
+
+/// A memory which can deallocate memory with a layout other than that of a single value.
+///
+/// This is required by `Pointers::retire_layout` for retired pointers whose layout is not that of
+/// a single `T` (e.g., a dynamically sized array or a block of an over-aligned pool).
+pub trait LayoutMemory: Memory {
+    /// Deallocates the memory with the supplied layout associated with the supplied pointer
+    /// without dropping the value it contains.
+    ///
+    /// # Safety
+    ///
+    /// The supplied pointer must have been allocated by this instance with the supplied layout,
+    /// must not have already been deallocated, and must not contain a value which still needs to
+    /// be dropped.
+    unsafe fn deallocate_layout(&self, pointer: *mut u8, layout: alloc::Layout);
+}
+
+impl<M> LayoutMemory for &M where M: LayoutMemory {
+    unsafe fn deallocate_layout(&self, pointer: *mut u8, layout: alloc::Layout) {
+        (**self).deallocate_layout(pointer, layout)
+    }
+}
+
+impl<M> LayoutMemory for Arc<M> where M: LayoutMemory {
+    unsafe fn deallocate_layout(&self, pointer: *mut u8, layout: alloc::Layout) {
+        (**self).deallocate_layout(pointer, layout)
+    }
+}
+
+// Memory ________________________________________
+
+/// A type that can allocate and deallocate memory.
+pub trait Memory {
+    /// Allocates memory.
+    fn allocate<T>(&self, value: T) -> *mut T;
+    /// Allocates memory or returns the supplied value if allocation fails.
+    ///
+    /// On success, the returned pointer owns the value as if it had been returned by `allocate`.
+    /// On failure, ownership of the value is returned to the caller. The default implementation
+    /// never fails and calls `allocate`.
+    fn try_allocate<T>(&self, value: T) -> Result<*mut T, T> {
+        Ok(self.allocate(value))
+    }
+    /// Deallocates the memory associated with the supplied pointer.
+    ///
+    /// # Safety
+    ///
+    /// The supplied pointer must have been returned by `allocate` on this instance and must not
+    /// have already been deallocated.
+    unsafe fn deallocate<T>(&self, pointer: *mut T);
+    /// Deallocates the memory associated with the supplied pointers.
+    ///
+    /// The default implementation deallocates each pointer individually but implementations which
+    /// can deallocate several pointers at once more efficiently should override it.
+    ///
+    /// # Safety
+    ///
+    /// Each supplied pointer must satisfy the requirements of `deallocate`.
+    unsafe fn deallocate_batch<T>(&self, pointers: &[*mut T]) {
+        for pointer in pointers {
+            self.deallocate(*pointer);
+        }
+    }
+}
+
+impl<M> Memory for &M where M: Memory {
+    fn allocate<T>(&self, value: T) -> *mut T {
+        (**self).allocate(value)
+    }
+
+    fn try_allocate<T>(&self, value: T) -> Result<*mut T, T> {
+        (**self).try_allocate(value)
+    }
+
+    unsafe fn deallocate<T>(&self, pointer: *mut T) {
+        (**self).deallocate(pointer)
+    }
+
+    unsafe fn deallocate_batch<T>(&self, pointers: &[*mut T]) {
+        (**self).deallocate_batch(pointers)
+    }
+}
+
+impl<M> Memory for Arc<M> where M: Memory {
+    fn allocate<T>(&self, value: T) -> *mut T {
+        (**self).allocate(value)
+    }
+
+    fn try_allocate<T>(&self, value: T) -> Result<*mut T, T> {
+        (**self).try_allocate(value)
+    }
+
+    unsafe fn deallocate<T>(&self, pointer: *mut T) {
+        (**self).deallocate(pointer)
+    }
+
+    unsafe fn deallocate_batch<T>(&self, pointers: &[*mut T]) {
+        (**self).deallocate_batch(pointers)
+    }
+}
+
+// Observer ______________________________________
+
+/// A type that observes reclamation events (e.g., to forward them to a logging framework).
+///
+/// If the `tracing` feature is enabled, the same events are also emitted with `tracing` (at the
+/// trace level for retired pointers and the debug level for reclamations) whether or not an
+/// observer is set.
+pub trait Observer<T> {
+    /// Called after the supplied pointer is retired using the supplied thread.
+    ///
+    /// The number of retired pointers that have not yet been reclaimed for the supplied thread
+    /// (including the supplied pointer) is specified by `pending`.
+    fn retired(&self, thread: usize, pointer: *mut T, pending: usize) {
+        let _ = (thread, pointer, pending);
+    }
+
+    /// Called after the retired pointers of the supplied thread are reclaimed.
+    ///
+    /// A report where nothing was freed and pointers were retained indicates that every retired
+    /// pointer of the supplied thread is still hazardous.
+    fn reclaimed(&self, thread: usize, report: ReclaimReport) {
+        let _ = (thread, report);
+    }
+
+    /// Called after the retired pointers of the supplied thread are reclaimed with the time it
+    /// took to reclaim them.
+    #[cfg(feature="timing")]
+    fn reclaimed_in(&self, thread: usize, duration: Duration, report: ReclaimReport) {
+        let _ = (thread, duration, report);
+    }
+}
+
+// PtrIdentity ___________________________________
+
+/// A method of determining whether a retired pointer is protected by a hazardous pointer.
+///
+/// A retired pointer is protected by a hazardous pointer if the address returned by `address` for
+/// the hazardous pointer is in the range returned by `range` for the retired pointer.
+pub trait PtrIdentity<T> {
+    /// Returns the address protected by the supplied hazardous pointer.
+    fn address(hazardous: *mut T) -> usize;
+
+    /// Returns the range of addresses which protect the supplied retired pointer.
+    fn range(retired: *mut T) -> ops::Range<usize>;
+}
+
+// Reclaimer _____________________________________
+
+/// A strategy for reclaiming retired pointers.
+pub trait Reclaimer<T, M, I=Address> where M: Memory, I: PtrIdentity<T> {
+    /// Deallocates some or all of the supplied retired pointers that are no longer hazardous.
+    ///
+    /// Pointers should be deallocated with `Pointers::kill` and must be removed from the list once
+    /// they have been deallocated. `Pointers::kill` and `Pointers::hazardous` fence before loading
+    /// the hazardous pointers, but reclaimers which load them directly (e.g., with
+    /// `Pointers::hazardous_iter`) must issue a sequentially consistent fence first.
+    fn reclaim(&self, pointers: &Pointers<T, M, I>, retired: &mut Vec<*mut T>);
+}
+
+// Retirable _____________________________________
+
+/// A type which contains the link used to thread it into an intrusive list of retired pointers.
+///
+/// # Safety
+///
+/// The link returned by `retire_link` must be a field of the value it is returned for and must not
+/// be used for anything else while the value is retired.
+pub unsafe trait Retirable: Sized {
+    /// Returns the link to the next retired value.
+    fn retire_link(&self) -> &AtomicPtr<Self>;
+}
+
+/// A function which returns the intrusive retire link of a pointer.
+type Link<T> = unsafe fn(*mut T) -> *const AtomicPtr<T>;
+
+/// Returns the intrusive retire link of the supplied pointer.
+unsafe fn link<T>(pointer: *mut T) -> *const AtomicPtr<T> where T: Retirable {
+    (*pointer).retire_link()
+}
+
+/// The head, length, and retire link accessor of an intrusive list of retired pointers.
+type Intrusive<T> = (*mut T, usize, Option<Link<T>>);
+
+/// A function which deallocates memory with a layout (see `LayoutMemory`).
+type DeallocateLayout<M> = unsafe fn(&M, *mut u8, alloc::Layout);
+
+/// A pointer retired with `Pointers::retire_layout`, its layout, and the function which
+/// deallocates it.
+type Layouted<T, M> = (*mut T, alloc::Layout, DeallocateLayout<M>);
+
+//================================================
+// Enums
+//================================================
+
+// FreeOrder _____________________________________
+
+/// The order in which a `Pointers` deallocates the retired pointers of a list when it is reclaimed.
+///
+/// Some allocators and pools reuse memory more effectively when it is freed in a particular order.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FreeOrder {
+    /// The oldest retired pointers are deallocated first.
+    #[default]
+    Fifo,
+    /// The newest retired pointers are deallocated first.
+    Lifo,
+}
+
+// PointersError _________________________________
+
+/// Indicates why an operation on a `Pointers` failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PointersError {
+    /// The supplied thread is out of range.
+    Thread(usize),
+    /// The supplied domain is out of range.
+    Domain(usize),
+    /// The retired pointers of the supplied thread are already borrowed (e.g., by a sweep during
+    /// which a destructor retired a pointer using the same thread).
+    Borrowed(usize),
+}
+
+impl fmt::Display for PointersError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PointersError::Thread(thread) => write!(formatter, "thread {} is out of range", thread),
+            PointersError::Domain(domain) => write!(formatter, "domain {} is out of range", domain),
+            PointersError::Borrowed(thread) => {
+                write!(formatter, "retired pointers of thread {} are already borrowed", thread)
+            },
+        }
+    }
+}
+
+impl error::Error for PointersError { }
+
+// ReclaimDecision _______________________________
+
+/// The decision made by a `DecisionScan` callback for a retired pointer which is not hazardous.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReclaimDecision {
+    /// The retired pointer is deallocated.
+    Free,
+    /// The retired pointer is removed from the list of retired pointers without being deallocated
+    /// (the callback takes ownership of it, e.g., to retire it elsewhere later). Kept pointers are
+    /// counted as freed by `ReclaimReport`.
+    Keep,
+    /// The retired pointer is left in the list of retired pointers to be decided on again by the
+    /// next reclamation.
+    Defer,
+}
+
+// RetiredLayout _________________________________
+
+/// Where a `Pointers` stores retired pointers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RetiredLayout {
+    /// Each thread has its own list of retired pointers.
+    ///
+    /// Retiring a pointer never contends with other threads, but each reclamation only considers
+    /// the pointers retired using one thread.
+    #[default]
+    PerThread,
+    /// Every thread retires pointers to a single shared list (the list orphaned pointers are
+    /// added to) which is protected by a lock.
+    ///
+    /// Retiring a pointer contends with every other thread that is retiring a pointer, but each
+    /// reclamation considers every retired pointer, so pointers are reclaimed more promptly. This
+    /// suits workloads with few threads.
+    Global,
+    /// Each thread has its own lock-free stack of retired pointers (the stack `retire_remote`
+    /// pushes onto).
+    ///
+    /// Retiring a pointer does not borrow the retired pointers of the thread, so any thread may
+    /// retire pointers using any thread (e.g., on behalf of a thread whose work it has stolen).
+    /// Once the stack reaches the threshold, the retiring thread pops the whole stack, reclaims it
+    /// with the reclaimer, and pushes the pointers that are still hazardous back onto it. Each
+    /// retired pointer requires an allocation for its node on the stack, so retiring a pointer
+    /// using a thread which is only used by one thread is about twice as slow as with
+    /// `RetiredLayout::PerThread` (see the `retired_layout` benchmark). The scan interval and the
+    /// duplicate guard of `Pointers::retire` do not apply.
+    ///
+    /// The other methods which borrow the retired pointers of a thread still borrow them: they
+    /// first move the stack into the retired pointers and push whatever they leave there back
+    /// onto the stack afterwards.
+    Stack,
+}
+
+// ThresholdPolicy _______________________________
+
+/// How a `Pointers` determines the threshold at which a list of retired pointers is reclaimed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ThresholdPolicy {
+    /// The threshold supplied when the collection was constructed is used.
+    #[default]
+    Fixed,
+    /// The threshold is the supplied number of retired pointers per active thread.
+    ///
+    /// If no thread has been registered, every thread is considered to be active. Otherwise, the
+    /// number of currently registered threads (but at least one) is used, so the threshold grows
+    /// and shrinks as threads register and unregister.
+    Scaled {
+        /// The number of retired pointers per active thread.
+        per_thread: usize,
+    },
+}
+
+//================================================
+// Structs
+//================================================
+
+// AbaGuard ______________________________________
+
+/// A memory which counts how many times each address has been deallocated so that the reuse of an
+/// address can be detected.
+///
+/// Memory implementations which reuse addresses (e.g., `SlabMemory`) expose compare-and-swap
+/// operations on pointers to the ABA problem: a pointer that was loaded, deallocated, and then
+/// reallocated at the same address compares equal to the pointer that was loaded. `AbaGuard` wraps
+/// such a memory and increments the generation of an address each time it is deallocated, so a
+/// `Versioned` pointer taken before the address was reused is no longer current.
+///
+/// This is opt-in and only detects the reuse of an address; it does not change which retired
+/// pointers are hazardous (a hazardous pointer still protects an address rather than a
+/// generation, see the crate documentation) and checking a version is not atomic with a
+/// compare-and-swap. A pointer cannot be deallocated while it is hazardous though, so if a
+/// version is current while its pointer is hazardous, the pointer refers to the allocation the
+/// version was taken for until the hazardous pointer is cleared.
+///
+/// ```
+/// use hazard::{AbaGuard, Memory, SlabMemory};
+///
+/// let memory = AbaGuard::new(SlabMemory::<u64>::new(1));
+/// let first = memory.allocate(17u64);
+/// let version = memory.version(first);
+///
+/// unsafe { memory.deallocate(first); }
+/// let second = memory.allocate(322u64);
+/// assert_eq!(second, version.pointer());
+/// assert!(!memory.is_current(version));
+/// # unsafe { memory.deallocate(second); }
+/// ```
+#[derive(Debug)]
+pub struct AbaGuard<M> where M: Memory {
+    memory: M,
+    generations: Mutex<HashMap<usize, usize>>,
+}
+
+impl<M> AbaGuard<M> where M: Memory {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `AbaGuard` which allocates and deallocates with the supplied memory.
+    pub fn new(memory: M) -> Self {
+        AbaGuard { memory, generations: Mutex::new(HashMap::new()) }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the memory this guard allocates and deallocates with.
+    pub fn memory(&self) -> &M {
+        &self.memory
+    }
+
+    fn generations(&self) -> MutexGuard<'_, HashMap<usize, usize>> {
+        self.generations.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the number of times the address of the supplied pointer has been deallocated.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn generation<T>(&self, pointer: *mut T) -> usize {
+        self.generations().get(&(pointer as usize)).cloned().unwrap_or(0)
+    }
+
+    /// Returns the supplied pointer along with the current generation of its address.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn version<T>(&self, pointer: *mut T) -> Versioned<T> {
+        Versioned { pointer, generation: self.generation(pointer) }
+    }
+
+    /// Returns whether the address of the supplied versioned pointer has not been deallocated
+    /// since the version was taken.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn is_current<T>(&self, versioned: Versioned<T>) -> bool {
+        self.generation(versioned.pointer) == versioned.generation
+    }
+
+    /// Increments the generations of the addresses of the supplied pointers.
+    fn advance<T>(&self, pointers: &[*mut T]) {
+        let mut generations = self.generations();
+        for pointer in pointers {
+            let generation = generations.entry(*pointer as usize).or_insert(0);
+            *generation = generation.wrapping_add(1);
+        }
+    }
+}
+
+impl<M> Memory for AbaGuard<M> where M: Memory {
+    fn allocate<T>(&self, value: T) -> *mut T {
+        self.memory.allocate(value)
+    }
+
+    fn try_allocate<T>(&self, value: T) -> Result<*mut T, T> {
+        self.memory.try_allocate(value)
+    }
+
+    unsafe fn deallocate<T>(&self, pointer: *mut T) {
+        self.advance(&[pointer]);
+        self.memory.deallocate(pointer);
+    }
+
+    unsafe fn deallocate_batch<T>(&self, pointers: &[*mut T]) {
+        self.advance(pointers);
+        self.memory.deallocate_batch(pointers);
+    }
+}
+
+impl<M> LayoutMemory for AbaGuard<M> where M: LayoutMemory {
+    unsafe fn deallocate_layout(&self, pointer: *mut u8, layout: alloc::Layout) {
+        self.advance(&[pointer]);
+        self.memory.deallocate_layout(pointer, layout);
+    }
+}
+
+// Address _______________________________________
+
+/// A pointer identity which compares addresses after clearing tag bits.
+///
+/// The tag bits are the low bits of a pointer which are always zero for a pointer to `T` due to the
+/// alignment of `T`. For untagged pointers, this is equivalent to `Exact`.
+///
+/// This is the default identity because readers of lock-free structures commonly load tagged
+/// pointers (e.g., a marked `next` pointer in a linked list) and mark them as hazardous without
+/// clearing the tag, while the pointer that is later retired is untagged. With `Exact`, such a
+/// hazardous pointer would silently fail to protect the value it refers to. Clearing the tag bits
+/// costs a single mask per hazardous pointer, which is not measurable next to the load of the
+/// hazardous pointer itself (see the `identity` benchmark), so `Exact` is only worth choosing when
+/// tagged pointers must be able to refer to distinct values.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Address;
+
+impl<T> PtrIdentity<T> for Address {
+    fn address(hazardous: *mut T) -> usize {
+        hazardous as usize & !(mem::align_of::<T>() - 1)
+    }
+
+    fn range(retired: *mut T) -> ops::Range<usize> {
+        let address = <Self as PtrIdentity<T>>::address(retired);
+        address..(address + 1)
+    }
+}
+
+// AlignVec ______________________________________
+
+#[cfg(target_arch="s390x")]
+const DEFAULT_CACHELINE: usize = 256;
+#[cfg(any(target_arch="x86_64", target_arch="aarch64", target_arch="powerpc64"))]
+const DEFAULT_CACHELINE: usize = 128;
+#[cfg(any(
+    target_arch="arm", target_arch="mips", target_arch="mips64", target_arch="sparc",
+    target_arch="hexagon",
+))]
+const DEFAULT_CACHELINE: usize = 32;
+#[cfg(not(any(
+    target_arch="s390x", target_arch="x86_64", target_arch="aarch64", target_arch="powerpc64",
+    target_arch="arm", target_arch="mips", target_arch="mips64", target_arch="sparc",
+    target_arch="hexagon",
+)))]
+const DEFAULT_CACHELINE: usize = 64;
+
+/// Parses a decimal integer at compile time.
+const fn parse(string: &str) -> usize {
+    let bytes = string.as_bytes();
+    let mut value = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        assert!(bytes[index].is_ascii_digit(), "HAZARD_CACHELINE_SIZE must be an integer");
+        value = (value * 10) + (bytes[index] - b'0') as usize;
+        index += 1;
+    }
+    value
+}
+
+/// The size of a cacheline in bytes that `AlignVec` is padded to.
+///
+/// The default depends on the target architecture and can be overridden by setting the
+/// `HAZARD_CACHELINE_SIZE` environment variable when building this crate.
+pub const CACHELINE: usize = match option_env!("HAZARD_CACHELINE_SIZE") {
+    Some(size) => parse(size),
+    None => DEFAULT_CACHELINE,
+};
+
+const POINTERS: usize = CACHELINE / mem::size_of::<usize>();
+
+const _: () = assert!(
+    CACHELINE % mem::size_of::<usize>() == 0 && POINTERS > 3,
+    "HAZARD_CACHELINE_SIZE must be a multiple of and more than three times the pointer size",
+);
+const _: () = assert!(mem::size_of::<AlignVec<u8>>() == CACHELINE);
+
+/// A `Vec` aligned to the size of a cacheline.
+///
+/// `AlignVec` formats like a `Vec` with `{:?}`, and also includes the cacheline size and the size
+/// of its padding in bytes with `{:#?}`.
+#[repr(C)]
+pub struct AlignVec<T> {
+    vec: Vec<T>,
+    _padding: [usize; POINTERS - 3],
+}
+
+impl<T> AlignVec<T> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `AlignVec`.
+    pub fn new(vec: Vec<T>) -> Self {
+        AlignVec { vec, _padding: [0; POINTERS - 3] }
+    }
+
+    /// Constructs a new empty `AlignVec` with the supplied capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(Vec::with_capacity(capacity))
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the number of elements this `AlignVec` can hold without reallocating.
+    ///
+    /// The padding used to align this `AlignVec` does not count towards this capacity.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns a pointer to the elements of this `AlignVec`.
+    pub fn as_ptr(&self) -> *const T {
+        self.vec.as_ptr()
+    }
+
+    /// Returns whether this `AlignVec` itself is located at an address which is a multiple of
+    /// `CACHELINE`.
+    ///
+    /// The padding of an `AlignVec` keeps adjacent values on separate cachelines only if the
+    /// surrounding allocation places it on a cacheline boundary, so this is useful for checking
+    /// that at runtime.
+    pub fn is_cacheline_aligned(&self) -> bool {
+        (self as *const Self as usize) % CACHELINE == 0
+    }
+}
+
+impl<T> Clone for AlignVec<T> where T: Clone {
+    fn clone(&self) -> Self {
+        Self::new(self.vec.clone())
+    }
+}
+
+impl<T> fmt::Debug for AlignVec<T> where T: fmt::Debug {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if formatter.alternate() {
+            formatter.debug_struct("AlignVec")
+                .field("vec", &self.vec)
+                .field("cacheline", &CACHELINE)
+                .field("padding", &mem::size_of_val(&self._padding))
+                .finish()
+        } else {
+            write!(formatter, "{:?}", &self.vec)
+        }
+    }
+}
+
+impl<T> ops::Deref for AlignVec<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.vec
+    }
+}
+
+impl<T> ops::DerefMut for AlignVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vec
+    }
+}
+
+// BatchScan _____________________________________
+
+/// A reclaimer that checks each retired pointer against every hazardous pointer and deallocates
+/// the non-hazardous pointers together with `Memory::deallocate_batch`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BatchScan;
+
+impl<T, M, I> Reclaimer<T, M, I> for BatchScan where M: Memory, I: PtrIdentity<T> {
+    fn reclaim(&self, pointers: &Pointers<T, M, I>, retired: &mut Vec<*mut T>) {
+        let (retained, freed) = retired.drain(..)
+            .partition::<Vec<_>, _>(|p| pointers.hazardous(*p));
+        unsafe { pointers.free_batch(&freed); }
+        *retired = retained;
+    }
+}
+
+// Bounded _______________________________________
+
+/// A reclaimer that checks no more than a fixed number of the oldest retired pointers against
+/// every hazardous pointer.
+///
+/// This bounds the latency of each reclamation at the cost of letting the list of retired pointers
+/// grow beyond the threshold when many of the oldest retired pointers are hazardous.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bounded {
+    /// The maximum number of retired pointers checked by each reclamation.
+    pub max_checks: usize,
+}
+
+impl<T, M, I> Reclaimer<T, M, I> for Bounded where M: Memory, I: PtrIdentity<T> {
+    fn reclaim(&self, pointers: &Pointers<T, M, I>, retired: &mut Vec<*mut T>) {
+        let mut checked = 0;
+        retired.retain(|p| {
+            checked += 1;
+            checked > self.max_checks || unsafe { !pointers.kill(*p) }
+        });
+    }
+}
+
+// BoxMemory _____________________________________
+
+/// An allocator that uses `Box` to allocate and deallocate memory.
+#[derive(Copy, Clone, Debug)]
+pub struct BoxMemory;
+
+impl Memory for BoxMemory {
+    fn allocate<T>(&self, value: T) -> *mut T {
+        Box::into_raw(Box::new(value))
+    }
+
+    fn try_allocate<T>(&self, value: T) -> Result<*mut T, T> {
+        let layout = alloc::Layout::new::<T>();
+        if layout.size() == 0 {
+            return Ok(self.allocate(value));
+        }
+
+        let pointer = unsafe { alloc::alloc(layout) } as *mut T;
+        if pointer.is_null() {
+            Err(value)
+        } else {
+            unsafe { pointer.write(value); }
+            Ok(pointer)
+        }
+    }
+
+    unsafe fn deallocate<T>(&self, pointer: *mut T) {
+        assert!(!pointer.is_null());
+        drop(Box::from_raw(pointer));
+    }
+}
+
+impl LayoutMemory for BoxMemory {
+    unsafe fn deallocate_layout(&self, pointer: *mut u8, layout: alloc::Layout) {
+        if layout.size() != 0 {
+            alloc::dealloc(pointer, layout);
+        }
+    }
+}
+
+unsafe impl Interchangeable for BoxMemory {
+    fn interchangeable(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+// DecisionScan __________________________________
+
+/// A reclaimer that checks each retired pointer against every hazardous pointer and then asks a
+/// callback what to do with each pointer that is not hazardous.
+///
+/// This generalizes the rule that retired pointers are deallocated as soon as they are not
+/// hazardous (e.g., destruction can be deferred until some external dependency is met). Hazardous
+/// pointers are never passed to the callback.
+///
+/// A pointer which the callback always defers is never deallocated (until the collection is
+/// dropped) and is rechecked by every reclamation, so the callback must eventually free or keep
+/// every pointer.
+pub struct DecisionScan<F> {
+    callback: F,
+}
+
+impl<F> DecisionScan<F> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `DecisionScan` which asks the supplied callback what to do with each
+    /// retired pointer which is not hazardous.
+    pub fn new(callback: F) -> Self {
+        DecisionScan { callback }
+    }
+}
+
+impl<T, M, I, F> Reclaimer<T, M, I> for DecisionScan<F>
+where M: Memory, I: PtrIdentity<T>, F: Fn(*mut T) -> ReclaimDecision {
+    fn reclaim(&self, pointers: &Pointers<T, M, I>, retired: &mut Vec<*mut T>) {
+        retired.retain(|p| {
+            if pointers.hazardous(*p) {
+                return true;
+            }
+
+            match (self.callback)(*p) {
+                ReclaimDecision::Free => {
+                    unsafe { pointers.free(*p); }
+                    false
+                },
+                ReclaimDecision::Keep => false,
+                ReclaimDecision::Defer => true,
+            }
+        });
+    }
+}
+
+impl<F> fmt::Debug for DecisionScan<F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("DecisionScan").finish()
+    }
+}
+
+// Domain ________________________________________
+
+/// The index of a hazardous pointer of a thread of a `Pointers`.
+///
+/// Methods which accept both a thread and a domain accept either a `usize` or this type for each
+/// so that calls like `mark(Thread(t), Domain(d), ...)` can't have their arguments transposed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Domain(pub usize);
+
+impl From<usize> for Domain {
+    fn from(domain: usize) -> Self {
+        Domain(domain)
+    }
+}
+
+// Exact _________________________________________
+
+/// A pointer identity which compares pointers exactly.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Exact;
+
+impl<T> PtrIdentity<T> for Exact {
+    fn address(hazardous: *mut T) -> usize {
+        hazardous as usize
+    }
+
+    fn range(retired: *mut T) -> ops::Range<usize> {
+        (retired as usize)..(retired as usize + 1)
+    }
+}
+
+// Hazard ________________________________________
+
+/// A hazardous pointer which is cleared when dropped.
+///
+/// A `Hazard` owns the domain it was created with for the supplied thread until it is dropped. The
+/// pointer protected by this domain can be replaced with `rebind` without clearing the domain in
+/// between, which is useful for traversing linked structures.
+#[must_use = "the hazardous pointer is cleared immediately if the `Hazard` is dropped"]
+pub struct Hazard<'p, T, M, I=Address>
+where T: 'p, M: 'p + Memory, I: 'p + PtrIdentity<T> {
+    pointers: &'p Pointers<T, M, I>,
+    thread: usize,
+    domain: usize,
+    pointer: *mut T,
+}
+
+impl<'p, T, M, I> Hazard<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Accessors --------------------------------
+
+    /// Returns the thread this hazardous pointer was set using.
+    pub fn thread(&self) -> usize {
+        self.thread
+    }
+
+    /// Returns the domain this hazardous pointer occupies.
+    pub fn domain(&self) -> usize {
+        self.domain
+    }
+
+    /// Returns the protected pointer.
+    pub fn as_ptr(&self) -> *mut T {
+        self.pointer
+    }
+
+    //- Mutators ---------------------------------
+
+    /// Replaces the protected pointer with the value of the supplied pointer and returns it.
+    ///
+    /// The domain is reused so the previously protected pointer is no longer protected once this
+    /// method returns.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn rebind(&mut self, pointer: &AtomicPtr<T>) -> *mut T {
+        self.pointer = self.pointers.mark(self.thread, self.domain, pointer);
+        self.pointer
+    }
+
+    /// Replaces the protected pointer with the supplied pointer and returns it.
+    ///
+    /// The domain is reused so the previously protected pointer is no longer protected once this
+    /// method returns.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn rebind_ptr(&mut self, pointer: *mut T) -> *mut T {
+        self.pointer = self.pointers.mark_ptr(self.thread, self.domain, pointer);
+        self.pointer
+    }
+}
+
+impl<'p, T, M, I> Drop for Hazard<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn drop(&mut self) {
+        self.pointers.clear(self.thread, self.domain);
+    }
+}
+
+impl<'p, T, M, I> fmt::Debug for Hazard<'p, T, M, I>
+where M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Hazard")
+            .field("thread", &self.thread)
+            .field("domain", &self.domain)
+            .field("pointer", &self.pointer)
+            .finish()
+    }
+}
+
+// Interior ______________________________________
+
+/// A pointer identity which considers a retired pointer protected by any hazardous pointer into
+/// the value it points to.
+///
+/// This allows readers to protect a pointer to a field of a value (e.g., `&node.payload` cast to
+/// `*mut T`) while the retired pointer still refers to the whole value. Checking whether a retired
+/// pointer is hazardous costs the same as with the other identities, but a hazardous pointer into
+/// the middle of one value never protects an adjacent value, so interior pointers must not point
+/// one past the end of a value.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Interior;
+
+impl<T> PtrIdentity<T> for Interior {
+    fn address(hazardous: *mut T) -> usize {
+        hazardous as usize
+    }
+
+    fn range(retired: *mut T) -> ops::Range<usize> {
+        (retired as usize)..(retired as usize + mem::size_of::<T>().max(1))
+    }
+}
+
+// HazardRegistry ________________________________
+
+/// An aggregation of the hazardous pointers of several collections.
+///
+/// This is useful for structures composed of several node types which each have their own
+/// `Pointers`.
+#[derive(Default)]
+pub struct HazardRegistry<'r> {
+    views: Vec<&'r dyn HazardView>,
+}
+
+impl<'r> HazardRegistry<'r> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new empty `HazardRegistry`.
+    pub fn new() -> Self {
+        HazardRegistry { views: vec![] }
+    }
+
+    //- Mutators ---------------------------------
+
+    /// Adds the supplied collection of hazardous pointers to this registry.
+    pub fn register(&mut self, view: &'r dyn HazardView) {
+        self.views.push(view);
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns whether the supplied address is considered hazardous by any registered collection.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`views * threads * domains`).
+    pub fn is_address_hazardous(&self, address: *const ()) -> bool {
+        self.views.iter().any(|v| v.is_address_hazardous(address))
+    }
+}
+
+impl<'r> fmt::Debug for HazardRegistry<'r> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("HazardRegistry").field("views", &self.views.len()).finish()
+    }
+}
+
+// HazardSet _____________________________________
+
+/// A set of hazardous pointers of a thread which are all cleared when the set is dropped.
+///
+/// Each pointer protected through a `HazardSet` is recorded along with its domain so operations
+/// which protect a varying number of pointers clear exactly the domains they used.
+///
+/// ```
+/// use hazard::{AtomicPtr, BoxMemory, Memory, Pointers};
+///
+/// let pointers = Pointers::new(BoxMemory, 1, 2, 8);
+/// let a = AtomicPtr::new(BoxMemory.allocate(17));
+/// let b = AtomicPtr::new(BoxMemory.allocate(322));
+///
+/// let mut set = pointers.hazard_set(0);
+/// let x = set.protect(0, &a);
+/// let y = set.protect(1, &b);
+/// assert_eq!(unsafe { *x + *y }, 339);
+/// set.release();
+/// assert_eq!(pointers.hazardous_iter().count(), 0);
+///
+/// pointers.retire(0, a.into_inner());
+/// pointers.retire(0, b.into_inner());
+/// ```
+#[must_use = "the hazardous pointers are cleared immediately if the `HazardSet` is dropped"]
+pub struct HazardSet<'p, T, M, I=Address>
+where T: 'p, M: 'p + Memory, I: 'p + PtrIdentity<T> {
+    pointers: &'p Pointers<T, M, I>,
+    thread: usize,
+    hazards: Vec<(usize, *mut T)>,
+}
+
+impl<'p, T, M, I> HazardSet<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Mutators ---------------------------------
+
+    /// Sets the hazardous pointer for the supplied domain to the value of the supplied pointer,
+    /// records it in this set, and returns it.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn protect(&mut self, domain: impl Into<Domain>, pointer: &AtomicPtr<T>) -> *mut T {
+        let domain = domain.into().0;
+        let pointer = self.pointers.mark(self.thread, domain, pointer);
+        self.hazards.push((domain, pointer));
+        pointer
+    }
+
+    /// Clears every hazardous pointer recorded in this set.
+    ///
+    /// This is equivalent to dropping this set.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`domains`).
+    pub fn release(self) { }
+
+    //- Accessors --------------------------------
+
+    /// Returns the thread the hazardous pointers in this set were set using.
+    pub fn thread(&self) -> usize {
+        self.thread
+    }
+
+    /// Returns the domains and protected pointers recorded in this set, in the order they were
+    /// protected.
+    pub fn hazards(&self) -> &[(usize, *mut T)] {
+        &self.hazards
+    }
+}
+
+impl<'p, T, M, I> Drop for HazardSet<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn drop(&mut self) {
+        for &(domain, _) in &self.hazards {
+            self.pointers.clear(self.thread, domain);
+        }
+    }
+}
+
+impl<'p, T, M, I> fmt::Debug for HazardSet<'p, T, M, I>
+where M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("HazardSet")
+            .field("thread", &self.thread)
+            .field("hazards", &self.hazards)
+            .finish()
+    }
+}
+
+// Metrics _______________________________________
+
+/// A snapshot of the reclamation metrics of a thread of a `Pointers`.
+///
+/// See `Pointers::metrics`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Metrics {
+    /// The number of retired pointers that have not yet been reclaimed.
+    pub pending: usize,
+    /// The largest number of retired pointers that have been waiting to be reclaimed at once.
+    pub high_water: usize,
+    /// The total number of pointers that have been retired.
+    pub retired: usize,
+    /// The total number of retired pointers that have been deallocated.
+    pub freed: usize,
+}
+
+// NaiveScan _____________________________________
+
+/// A reclaimer that checks each retired pointer against every hazardous pointer.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NaiveScan;
+
+impl<T, M, I> Reclaimer<T, M, I> for NaiveScan where M: Memory, I: PtrIdentity<T> {
+    fn reclaim(&self, pointers: &Pointers<T, M, I>, retired: &mut Vec<*mut T>) {
+        retired.retain(|p| unsafe { !pointers.kill(*p) });
+    }
+}
+
+// PinGuard ______________________________________
+
+/// A guard which defers reclamation for a thread of a `Pointers` until it is dropped.
+///
+/// See `Pointers::pin` and `Pointers::pin_all`.
+#[must_use = "the thread is unpinned immediately if the `PinGuard` is dropped"]
+pub struct PinGuard<'p, T, M, I=Address>
+where T: 'p, M: 'p + Memory, I: 'p + PtrIdentity<T> {
+    pointers: &'p Pointers<T, M, I>,
+    thread: usize,
+    global: bool,
+}
+
+impl<'p, T, M, I> PinGuard<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Accessors --------------------------------
+
+    /// Returns the thread this guard pins.
+    pub fn thread(&self) -> usize {
+        self.thread
+    }
+
+    /// Returns whether this guard pins every thread.
+    pub fn is_global(&self) -> bool {
+        self.global
+    }
+}
+
+impl<'p, T, M, I> Drop for PinGuard<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn drop(&mut self) {
+        if self.global {
+            self.pointers.pins.fetch_sub(1, SeqCst);
+        }
+        self.pointers.pinned[self.thread].fetch_sub(1, Relaxed);
+    }
+}
+
+impl<'p, T, M, I> fmt::Debug for PinGuard<'p, T, M, I>
+where M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("PinGuard")
+            .field("thread", &self.thread)
+            .field("global", &self.global)
+            .finish()
+    }
+}
+
+// Pointers ______________________________________
+
+/// A node in a lock-free stack of pointers retired using `Pointers::retire_remote`.
+struct Remote<T> {
+    pointer: *mut T,
+    next: *mut Remote<T>,
+}
+
+/// The reclamation metrics of a thread, mirrored from its retired pointers.
+#[derive(Debug, Default)]
+struct Tally {
+    pending: sync::AtomicUsize,
+    high_water: sync::AtomicUsize,
+    retired: sync::AtomicUsize,
+    freed: sync::AtomicUsize,
+}
+
+/// A future which reclaims the retired pointers of a collection until it is quiescent, waiting
+/// for a timer between attempts.
+#[cfg(feature="async")]
+struct Quiescence<'p, T, M, I, S, F> where T: 'p, M: 'p + Memory, I: 'p + PtrIdentity<T> {
+    pointers: &'p Pointers<T, M, I>,
+    interval: Duration,
+    sleep: S,
+    timer: Option<Pin<Box<F>>>,
+}
+
+// The timer is pinned in its own allocation and the sleep function is never pinned.
+#[cfg(feature="async")]
+impl<'p, T, M, I, S, F> Unpin for Quiescence<'p, T, M, I, S, F>
+where M: Memory, I: PtrIdentity<T> { }
+
+#[cfg(feature="async")]
+impl<'p, T, M, I, S, F> Future for Quiescence<'p, T, M, I, S, F>
+where M: Memory, I: PtrIdentity<T>, S: FnMut(Duration) -> F, F: Future<Output=()> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        let quiescence = &mut *self;
+        loop {
+            if let Some(ref mut timer) = quiescence.timer {
+                if timer.as_mut().poll(context).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+
+            quiescence.pointers.reclaim_all();
+            if quiescence.pointers.is_quiescent() {
+                quiescence.timer = None;
+                return Poll::Ready(());
+            }
+            quiescence.timer = Some(Box::pin((quiescence.sleep)(quiescence.interval)));
+        }
+    }
+}
+
+#[cfg(not(loom))]
+thread_local! {
+    /// The address of the collection and the thread whose retired pointers are being reclaimed
+    /// on the current thread.
+    static SWEEPING: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+#[cfg(loom)]
+loom::thread_local! {
+    /// The address of the collection and the thread whose retired pointers are being reclaimed
+    /// on the current `loom` thread.
+    static SWEEPING: Cell<(usize, usize)> = Cell::new((0, 0));
+}
+
+thread_local! {
+    /// The identifiers of the collections and the threads registered with them on the current
+    /// thread.
+    static REGISTERED: RefCell<Vec<(usize, usize)>> = const { RefCell::new(vec![]) };
+}
+
+/// The identifier of the next collection constructed.
+#[cfg(not(loom))]
+static IDS: sync::AtomicUsize = sync::AtomicUsize::new(0);
+/// The identifier of the next collection constructed (the atomic types of `loom` can't be used in
+/// statics).
+#[cfg(loom)]
+static IDS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// A collection of hazardous pointers.
+///
+/// The retired values should be owned nodes; retiring pointers to references (e.g., with
+/// `Pointers<&U, _>`) is almost always a mistake. A collection drops the values of its retired
+/// pointers, so the compiler rejects a collection of references which outlives what they borrow:
+///
+/// ```compile_fail
+/// use hazard::{BoxMemory, Memory, Pointers};
+///
+/// let pointers;
+/// {
+///     let string = String::from("borrowed");
+///     pointers = Pointers::new(BoxMemory, 1, 1, 2);
+///     pointers.retire(0, BoxMemory.allocate(&string));
+/// }
+/// ```
+#[repr(C)]
+pub struct Pointers<T, M, I=Address> where M: Memory, I: PtrIdentity<T> {
+    hazardous: AlignVec<Vec<sync::AtomicPtr<T>>>,
+    retired: AlignVec<RefCell<Vec<*mut T>>>,
+    threshold: sync::AtomicUsize,
+    memory: M,
+    reclaimer: Box<dyn Reclaimer<T, M, I>>,
+    reclaim_on_clear: usize,
+    remote: AlignVec<sync::AtomicPtr<Remote<T>>>,
+    stacked: AlignVec<sync::AtomicUsize>,
+    observer: Option<Box<dyn Observer<T>>>,
+    intrusive: AlignVec<Cell<Intrusive<T>>>,
+    registered: Vec<sync::AtomicBool>,
+    dynamic: sync::AtomicBool,
+    orphans: Mutex<Vec<*mut T>>,
+    pinned: AlignVec<sync::AtomicUsize>,
+    pins: sync::AtomicUsize,
+    order: FreeOrder,
+    lane: sync::AtomicUsize,
+    offsets: Vec<sync::AtomicUsize>,
+    forget: sync::AtomicBool,
+    interval: sync::AtomicUsize,
+    retires: AlignVec<Cell<usize>>,
+    last: AlignVec<sync::AtomicUsize>,
+    skip_own: bool,
+    tallies: AlignVec<Tally>,
+    id: usize,
+    policy: ThresholdPolicy,
+    active: sync::AtomicUsize,
+    slots: usize,
+    layout: RetiredLayout,
+    deferred: AlignVec<Cell<Vec<*mut T>>>,
+    layouts: AlignVec<RefCell<Vec<Layouted<T, M>>>>,
+    identity: PhantomData<I>,
+}
+
+impl<T, M> Pointers<T, M> where M: Memory {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Pointers`.
+    ///
+    /// The maximum number of threads is specified by `threads` and the maximum number of hazardous
+    /// pointers per thread is specified by `domains`.
+    ///
+    /// The maximum size lists of retired pointers can grow to is specified by `threshold`. Once a
+    /// list of retired pointers reaches this limit, any pointers that are no longer hazardous are
+    /// removed from the list and the memory they refer to is deallocated.
+    ///
+    /// See `PointersBuilder` for additional options. See `Pointers` for why `T` should not be a
+    /// reference type.
+    pub fn new(memory: M, threads: usize, domains: usize, threshold: usize) -> Self {
+        PointersBuilder::new(memory, threads, domains, threshold).build()
+    }
+
+    /// Constructs a new `Pointers` or returns an error if allocation fails.
+    ///
+    /// See `new` for the meaning of the parameters.
+    pub fn try_new(
+        memory: M, threads: usize, domains: usize, threshold: usize
+    ) -> Result<Self, TryReserveError> {
+        PointersBuilder::new(memory, threads, domains, threshold).try_build()
+    }
+
+    /// Constructs a new `Pointers` with the dimensions and threshold of the supplied configuration.
+    ///
+    /// This allows collections for different types and with different memory to share tuning.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use hazard::{BoxMemory, Pointers, PointersConfig};
+    ///
+    /// let config = PointersConfig { threads: 4, domains: 2, threshold: 64 };
+    /// let strings = Pointers::<String, _>::from_config_and_memory(config, BoxMemory);
+    /// let numbers = Pointers::<u64, _>::from_config_and_memory(config, Arc::new(BoxMemory));
+    /// assert_eq!(strings.config(), numbers.config());
+    /// ```
+    pub fn from_config_and_memory(config: PointersConfig, memory: M) -> Self {
+        Pointers::new(memory, config.threads, config.domains, config.threshold)
+    }
+
+    /// Constructs a new `Pointers` which reclaims retired pointers with the supplied reclaimer.
+    ///
+    /// See `new` for the meaning of the other parameters.
+    pub fn with_reclaimer<R>(
+        memory: M, threads: usize, domains: usize, threshold: usize, reclaimer: R
+    ) -> Self where R: Reclaimer<T, M> + 'static {
+        PointersBuilder::new(memory, threads, domains, threshold).reclaimer(reclaimer).build()
+    }
+}
+
+impl<T, M, I> Pointers<T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Pointers` which uses the supplied pointer identity to determine whether
+    /// retired pointers are hazardous.
+    ///
+    /// See `new` for the meaning of the parameters.
+    pub fn with_identity(memory: M, threads: usize, domains: usize, threshold: usize) -> Self {
+        PointersBuilder::with_identity(memory, threads, domains, threshold).build()
+    }
+
+    /// Constructs a new `Pointers` from a builder.
+    fn from_builder(builder: PointersBuilder<T, M, I>) -> Self {
+        Self::try_from_builder(builder).expect("failed to allocate Pointers")
+    }
+
+    /// Constructs a new `Pointers` from a builder or returns an error if allocation fails.
+    fn try_from_builder(builder: PointersBuilder<T, M, I>) -> Result<Self, TryReserveError> {
+        let PointersBuilder {
+            memory,
+            threads,
+            domains,
+            threshold,
+            reclaimer,
+            reclaim_on_clear,
+            retire_only,
+            order,
+            skip_own,
+            policy,
+            layout,
+        } = builder;
+        let readers = threads.saturating_sub(retire_only);
+        let mut hazardous = Vec::new();
+        hazardous.try_reserve_exact(threads)?;
+        for thread in 0..threads {
+            let domains = if thread < readers { domains } else { 0 };
+            hazardous.push(try_collect(domains, |_| sync::AtomicPtr::new(ptr::null_mut()))?);
+        }
+        Ok(Pointers {
+            hazardous: AlignVec::new(hazardous),
+            retired: AlignVec::new(try_collect(threads, |_| RefCell::new(vec![]))?),
+            threshold: sync::AtomicUsize::new(threshold),
+            memory,
+            reclaimer,
+            reclaim_on_clear,
+            remote: AlignVec::new(try_collect(threads, |_| sync::AtomicPtr::new(ptr::null_mut()))?),
+            stacked: AlignVec::new(try_collect(threads, |_| sync::AtomicUsize::new(0))?),
+            observer: None,
+            intrusive: AlignVec::new(try_collect(threads, |_| {
+                Cell::new((ptr::null_mut(), 0, None))
+            })?),
+            registered: try_collect(threads, |_| sync::AtomicBool::new(false))?,
+            dynamic: sync::AtomicBool::new(false),
+            orphans: Mutex::new(vec![]),
+            pinned: AlignVec::new(try_collect(threads, |_| sync::AtomicUsize::new(0))?),
+            pins: sync::AtomicUsize::new(0),
+            order,
+            lane: sync::AtomicUsize::new(0),
+            offsets: try_collect(threads, |_| sync::AtomicUsize::new(0))?,
+            forget: sync::AtomicBool::new(false),
+            interval: sync::AtomicUsize::new(0),
+            retires: AlignVec::new(try_collect(threads, |_| Cell::new(0))?),
+            last: AlignVec::new(try_collect(threads, |_| sync::AtomicUsize::new(usize::MAX))?),
+            skip_own,
+            tallies: AlignVec::new(try_collect(threads, |_| Tally::default())?),
+            id: IDS.fetch_add(1, Relaxed),
+            policy,
+            active: sync::AtomicUsize::new(0),
+            slots: readers * domains,
+            layout,
+            deferred: AlignVec::new(try_collect(threads, |_| Cell::new(vec![]))?),
+            layouts: AlignVec::new(try_collect(threads, |_| RefCell::new(vec![]))?),
+            identity: PhantomData,
+        })
+    }
+
+    /// Constructs a new `Pointers` for another type which shares the memory of this collection.
+    ///
+    /// The new collection borrows the memory of this collection so it can't outlive it. See `new`
+    /// for the meaning of the parameters.
+    pub fn fork<'a, U>(
+        &'a self, threads: usize, domains: usize, threshold: usize
+    ) -> Pointers<U, &'a M> where &'a M: Memory {
+        Pointers::new(&self.memory, threads, domains, threshold)
+    }
+
+    //- Mutators ---------------------------------
+
+    /// Sets the observer that is notified of reclamation events.
+    pub fn set_observer<O>(&mut self, observer: O) where O: Observer<T> + 'static {
+        self.observer = Some(Box::new(observer));
+    }
+
+    /// Appends the threads of the supplied collection to this collection and returns the index
+    /// that the first thread of the supplied collection has in this collection.
+    ///
+    /// Thread `i` of the supplied collection becomes thread `index + i` of this collection, where
+    /// `index` is the returned index (the previous number of threads of this collection), along
+    /// with its hazardous pointers, retired pointers, and registration. The orphaned
+    /// pointers of the supplied collection are added to those of this collection. Everything else
+    /// (e.g., the threshold, reclaimer, and observer) is that of this collection. Threads which
+    /// were registered with the supplied collection using `register` can't be unregistered with
+    /// `unregister_current` on this collection; use `unregister` with the remapped index instead.
+    ///
+    /// The retired pointers of the supplied collection will be deallocated with the memory of
+    /// this collection, so the memories of both collections must be interchangeable (see
+    /// `Interchangeable`). Memories which own their allocations (e.g., `SlabMemory`) must be
+    /// shared with a reference or an `Arc` instead:
+    ///
+    /// ```
+    /// use std::sync::{Arc};
+    ///
+    /// use hazard::{Memory, Pointers, SlabMemory};
+    ///
+    /// let slab = Arc::new(SlabMemory::<u64>::new(4));
+    /// let mut pointers = Pointers::new(slab.clone(), 2, 1, 8);
+    /// let other = Pointers::new(slab.clone(), 1, 1, 8);
+    /// other.retire(0, slab.allocate(17));
+    ///
+    /// assert_eq!(pointers.merge(other), 2);
+    /// assert_eq!(pointers.pending(2), 1);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use hazard::{Memory, Pointers, SlabMemory};
+    ///
+    /// let mut pointers = Pointers::<u64, _>::new(SlabMemory::<u64>::new(4), 2, 1, 8);
+    /// let other = Pointers::new(SlabMemory::<u64>::new(4), 1, 1, 8);
+    /// pointers.merge(other);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This panics if the memories of the collections are not interchangeable.
+    pub fn merge(&mut self, mut other: Pointers<T, M, I>) -> usize where M: Interchangeable {
+        assert!(
+            self.memory.interchangeable(&other.memory),
+            "merged a collection whose memory is not interchangeable with this collection's",
+        );
+        let index = self.retired.len();
+        self.hazardous.append(&mut other.hazardous);
+        self.retired.append(&mut other.retired);
+        self.remote.append(&mut other.remote);
+        self.stacked.append(&mut other.stacked);
+        self.intrusive.append(&mut other.intrusive);
+        self.registered.append(&mut other.registered);
+        self.pinned.append(&mut other.pinned);
+        self.offsets.append(&mut other.offsets);
+        self.retires.append(&mut other.retires);
+        self.last.append(&mut other.last);
+        self.tallies.append(&mut other.tallies);
+        self.deferred.append(&mut other.deferred);
+        self.layouts.append(&mut other.layouts);
+        self.orphans.get_mut().unwrap_or_else(|e| e.into_inner())
+            .append(other.orphans.get_mut().unwrap_or_else(|e| e.into_inner()));
+        self.dynamic.fetch_or(other.dynamic.load(Relaxed), Relaxed);
+        self.active.fetch_add(other.active.load(Relaxed), Relaxed);
+        self.slots += other.slots;
+        index
+    }
+
+    /// Moves the supplied range of threads of this collection to a new collection and returns it.
+    ///
+    /// Thread `range.start + i` of this collection becomes thread `i` of the new collection along
+    /// with its hazardous pointers, retired pointers, and registration, and the threads of this
+    /// collection after the range are renumbered to fill the gap. The new collection has the same
+    /// threshold and options as this collection, but it uses `NaiveScan` as its reclaimer and has
+    /// no observer. Like with `merge`, moved threads can't be unregistered with
+    /// `unregister_current`.
+    ///
+    /// This takes `&mut self`, so none of the moved threads can be in use. However, indices held
+    /// elsewhere (e.g., by threads which will use the new collection) must be remapped.
+    ///
+    /// ```
+    /// use hazard::{BoxMemory, Memory, Pointers};
+    ///
+    /// let mut pointers = Pointers::new(BoxMemory, 4, 1, 8);
+    /// pointers.retire(2, BoxMemory.allocate(17));
+    ///
+    /// let other = pointers.split_off(1..3);
+    /// assert_eq!((pointers.config().threads, other.config().threads), (2, 2));
+    /// assert_eq!(other.pending(1), 1);
+    /// ```
+    ///
+    /// The retired pointers of the moved threads will be deallocated with a clone of the memory of
+    /// this collection, so the clone must be interchangeable with it (see `Interchangeable`).
+    ///
+    /// # Panics
+    ///
+    /// This panics if the supplied range is out of bounds or if the clone of the memory of this
+    /// collection is not interchangeable with it.
+    pub fn split_off(
+        &mut self, range: ops::Range<usize>
+    ) -> Pointers<T, M, I> where M: Clone + Interchangeable {
+        let memory = self.memory.clone();
+        assert!(
+            memory.interchangeable(&self.memory),
+            "split off a collection whose memory is not interchangeable with this collection's",
+        );
+        let threshold = self.threshold.load(Relaxed);
+        let mut other = PointersBuilder::with_identity(memory, 0, 0, threshold)
+            .reclaim_on_clear(self.reclaim_on_clear)
+            .free_order(self.order)
+            .skip_own_domains(self.skip_own)
+            .threshold_policy(self.policy)
+            .retired_layout(self.layout)
+            .build();
+        other.hazardous.extend(self.hazardous.drain(range.clone()));
+        other.retired.extend(self.retired.drain(range.clone()));
+        other.remote.extend(self.remote.drain(range.clone()));
+        other.stacked.extend(self.stacked.drain(range.clone()));
+        other.intrusive.extend(self.intrusive.drain(range.clone()));
+        other.registered.extend(self.registered.drain(range.clone()));
+        other.pinned.extend(self.pinned.drain(range.clone()));
+        other.offsets.extend(self.offsets.drain(range.clone()));
+        other.retires.extend(self.retires.drain(range.clone()));
+        other.last.extend(self.last.drain(range.clone()));
+        other.tallies.extend(self.tallies.drain(range.clone()));
+        other.deferred.extend(self.deferred.drain(range.clone()));
+        other.layouts.extend(self.layouts.drain(range));
+        let active = other.registered.iter().filter(|r| r.load(Relaxed)).count();
+        self.active.fetch_sub(active, Relaxed);
+        other.active.store(active, Relaxed);
+        other.dynamic.store(self.dynamic.load(Relaxed), Relaxed);
+        other.slots = other.hazardous.iter().map(Vec::len).sum();
+        self.slots -= other.slots;
+        other.interval.store(self.interval.load(Relaxed), Relaxed);
+        self.lane.store(0, Relaxed);
+        other
+    }
+
+    /// Sets the maximum size lists of retired pointers can grow to (see `new`).
+    ///
+    /// This may be called while other threads are retiring pointers; each thread uses the new
+    /// threshold the next time it retires a pointer. With `ThresholdPolicy::Scaled`, the threshold
+    /// is determined by the policy instead (although `config` still reports this threshold).
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn set_threshold(&self, threshold: usize) {
+        self.threshold.store(threshold, Relaxed);
+    }
+
+    /// Sets the number of pointers retired using a thread after which its retired pointers are
+    /// reclaimed regardless of the threshold (defaults to `0` which disables the interval).
+    ///
+    /// Retired pointers are reclaimed when either the threshold is reached or the interval
+    /// elapses, so a slowly growing list of retired pointers (e.g., one whose pointers stay
+    /// hazardous for a long time) is still reclaimed periodically.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn set_scan_interval(&self, interval: usize) {
+        self.interval.store(interval, Relaxed);
+    }
+
+    /// Sets whether dropping this collection leaks the retired pointers rather than deallocating
+    /// them (defaults to `false`).
+    ///
+    /// This is an escape hatch for shutting down quickly (e.g., when the process is about to exit)
+    /// or when other threads may still be reading the retired pointers. If set, dropping this
+    /// collection does not deallocate any retired or orphaned pointers, run their destructors, or
+    /// check whether any hazardous pointers are still set.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn set_forget_on_drop(&self, forget: bool) {
+        self.forget.store(forget, Relaxed);
+    }
+
+    //- Accessors --------------------------------
+
+    /// Registers an unregistered thread which has hazardous pointers and returns its index, or
+    /// `None` if every such thread is registered.
+    ///
+    /// Registration is optional; threads may instead be assigned fixed indices. Once any thread
+    /// has been registered, however, the whole collection uses registration: pointers retired
+    /// using any unregistered thread (including a fixed index that was never registered) are moved
+    /// to a shared list of orphaned pointers since no thread owns the list they would otherwise be
+    /// added to. This switch is permanent; it is not undone when every thread is unregistered.
+    /// Like the list of retired pointers of a thread, the shared list is reclaimed once it reaches
+    /// the threshold, so such pointers do not accumulate.
+    ///
+    /// Retire-only threads (see `PointersBuilder::retire_only`) are never returned by this method.
+    ///
+    /// The returned thread is also recorded for the current thread so it can be released with
+    /// `unregister_current`.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads`).
+    pub fn register(&self) -> Option<usize> {
+        self.register_with(false)
+    }
+
+    /// Registers an unregistered retire-only thread and returns its index, or `None` if every
+    /// retire-only thread is registered.
+    ///
+    /// See `register` and `PointersBuilder::retire_only`.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads`).
+    pub fn register_retire_only(&self) -> Option<usize> {
+        self.register_with(true)
+    }
+
+    /// Registers an unregistered thread which has no hazardous pointers if `retire_only` is
+    /// `true` or which has hazardous pointers otherwise.
+    fn register_with(&self, retire_only: bool) -> Option<usize> {
+        self.dynamic.store(true, Relaxed);
+        let thread = self.registered.iter().enumerate().position(|(thread, r)| {
+            self.hazardous[thread].is_empty() == retire_only &&
+                r.compare_exchange(false, true, AcqRel, Relaxed).is_ok()
+        })?;
+        self.active.fetch_add(1, Relaxed);
+        REGISTERED.with(|r| r.borrow_mut().push((self.id, thread)));
+        Some(thread)
+    }
+
+    /// Removes and returns the most recent thread registered on the current thread, or the
+    /// supplied thread if it was registered on the current thread.
+    fn forget_registration(&self, thread: Option<usize>) -> Option<usize> {
+        REGISTERED.try_with(|r| {
+            let mut registered = r.borrow_mut();
+            let index = registered.iter().rposition(|&(id, t)| {
+                id == self.id && thread.is_none_or(|thread| thread == t)
+            })?;
+            Some(registered.remove(index).1)
+        }).ok().flatten()
+    }
+
+    /// Unregisters the supplied thread.
+    ///
+    /// The hazardous pointers for the supplied thread are cleared, its retired pointers are
+    /// reclaimed, and any that are still hazardous are moved to the shared list of orphaned
+    /// pointers.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn unregister(&self, thread: usize) {
+        self.forget_registration(Some(thread));
+        self.release(thread);
+    }
+
+    /// Unregisters the thread most recently registered on the current thread with `register` or
+    /// `register_retire_only` and returns its index, or `None` if no thread registered on the
+    /// current thread is still registered.
+    ///
+    /// This is like `unregister` except that the thread to unregister is remembered from its
+    /// registration, which suits thread pools that reuse threads and the path a thread takes when
+    /// it exits. Each registration is released at most once (including by `unregister`), so this
+    /// does nothing if the current thread never registered a thread or has already released it.
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::thread;
+    ///
+    /// use hazard::{BoxMemory, Memory, Pointers};
+    ///
+    /// static DROPPED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// struct Node(usize);
+    ///
+    /// impl Drop for Node {
+    ///     fn drop(&mut self) {
+    ///         DROPPED.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// struct Shared(Pointers<Node, BoxMemory>);
+    ///
+    /// unsafe impl Sync for Shared { }
+    ///
+    /// let shared = Shared(Pointers::new(BoxMemory, 4, 1, 16));
+    /// for _ in 0..8 {
+    ///     thread::scope(|scope| {
+    ///         for _ in 0..4 {
+    ///             let shared = &shared;
+    ///             scope.spawn(move || {
+    ///                 let thread = shared.0.register().unwrap();
+    ///                 for value in 0..10 {
+    ///                     shared.0.retire(thread, BoxMemory.allocate(Node(value)));
+    ///                 }
+    ///                 assert_eq!(shared.0.unregister_current(), Some(thread));
+    ///                 assert_eq!(shared.0.unregister_current(), None);
+    ///             });
+    ///         }
+    ///     });
+    /// }
+    /// assert_eq!(shared.0.orphaned(), 0);
+    /// assert_eq!(DROPPED.load(Ordering::Relaxed), 8 * 4 * 10);
+    /// ```
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn unregister_current(&self) -> Option<usize> {
+        let thread = self.forget_registration(None)?;
+        self.release(thread);
+        Some(thread)
+    }
+
+    /// Clears the hazardous pointers of the supplied thread, reclaims or orphans its retired
+    /// pointers, and marks it as unregistered.
+    fn release(&self, thread: usize) {
+        for hazardous in &self.hazardous[thread] {
+            hazardous.store(ptr::null_mut(), Release);
+        }
+        let mut retired = self.retired[thread].borrow_mut();
+        self.sweep(thread, &mut retired);
+        self.adopt(thread, &mut retired);
+        self.push_shared(retired.drain(..));
+        self.tally(thread, 0, 0, 0);
+        if self.registered[thread].swap(false, Release) {
+            self.active.fetch_sub(1, Relaxed);
+        }
+    }
+
+    /// Returns whether the supplied thread is registered.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn registered(&self, thread: usize) -> bool {
+        self.registered[thread].load(Acquire)
+    }
+
+    fn orphans(&self) -> MutexGuard<'_, Vec<*mut T>> {
+        self.orphans.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the number of orphaned pointers that have not yet been reclaimed.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn orphaned(&self) -> usize {
+        self.orphans().len()
+    }
+
+    /// Returns the memory used to deallocate retired pointers.
+    pub fn memory(&self) -> &M {
+        &self.memory
+    }
+
+    /// Returns the dimensions and threshold of this collection.
+    ///
+    /// The other options that can be set with `PointersBuilder` are not included.
+    pub fn config(&self) -> PointersConfig {
+        PointersConfig {
+            threads: self.hazardous.len(),
+            domains: self.hazardous.iter().map(|h| h.len()).max().unwrap_or(0),
+            threshold: self.threshold.load(Relaxed),
+        }
+    }
+
+    /// Hints to the processor that the hazardous pointers and retired pointers of the supplied
+    /// thread will be used soon.
+    ///
+    /// This is a best-effort micro-optimization for latency-sensitive readers which can call it
+    /// ahead of an operation so that the first `mark` is less likely to miss the cache. It issues
+    /// prefetch instructions on `x86_64` targets with SSE and is a no-op on other targets. It has no
+    /// other effect.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`domains`).
+    pub fn warm(&self, thread: usize) {
+        for hazardous in &self.hazardous[thread] {
+            prefetch(hazardous);
+        }
+        prefetch(&self.retired[thread]);
+        if let Ok(retired) = self.retired[thread].try_borrow() {
+            prefetch(retired.as_ptr());
+        }
+    }
+
+    /// Returns an estimate of the fraction of hazardous pointers which are set, computed by loading
+    /// the supplied number of randomly chosen hazardous pointers.
+    ///
+    /// This is much cheaper than loading every hazardous pointer when there are many threads, so
+    /// it is suitable for frequent decisions such as adapting the threshold to the current
+    /// protection pressure. It is only an estimate: the sampling error is proportional to
+    /// `1 / sqrt(samples)`. Each hazardous pointer is equally likely to be chosen, even if the
+    /// threads have different numbers of domains (e.g., after `merge`). Returns `0.0` if no
+    /// samples are taken or there are no hazardous pointers.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads + samples`).
+    pub fn sample_hazards(&self, samples: usize) -> f64 {
+        if samples == 0 || self.slots == 0 {
+            return 0.0;
+        }
+
+        // A thread and a domain below the largest number of domains are chosen uniformly and the
+        // choice is rejected if the thread has fewer domains, so every hazardous pointer is equally
+        // likely. On average, each sample takes `cells / slots` attempts.
+        let domains = self.hazardous.iter().map(Vec::len).max().unwrap_or(0);
+        let cells = self.hazardous.len() * domains;
+        let attempts = samples.saturating_mul(2 * cells.div_ceil(self.slots));
+        let (threads, domains) = (self.hazardous.len() as u64, domains as u64);
+        let (mut sampled, mut set) = (0, 0);
+        for _ in 0..attempts {
+            let row = &self.hazardous[(random() % threads) as usize];
+            let hazardous = match row.get((random() % domains) as usize) {
+                Some(hazardous) => hazardous,
+                None => continue,
+            };
+
+            set += usize::from(!hazardous.load(Relaxed).is_null());
+            sampled += 1;
+            if sampled == samples {
+                break;
+            }
+        }
+        set as f64 / sampled.max(1) as f64
+    }
+
+    /// Returns an estimate of the number of bytes occupied by this collection itself.
+    ///
+    /// This includes the hazardous pointers, the padding used to align them, and the capacities of
+    /// the lists of retired pointers, but not the memory referred to by any pointers.
+    ///
+    /// This method borrows the retired list of every thread so it must not be called while any
+    /// thread is retiring pointers.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn capacity_bytes(&self) -> usize {
+        let hazardous = self.hazardous.iter().map(|h| h.capacity()).sum::<usize>();
+        let retired = self.retired.iter().map(|r| r.borrow().capacity()).sum::<usize>();
+        let orphans = self.orphans().capacity();
+        mem::size_of::<Self>() +
+            (hazardous * mem::size_of::<sync::AtomicPtr<T>>()) +
+            (self.hazardous.capacity() * mem::size_of::<Vec<sync::AtomicPtr<T>>>()) +
+            ((retired + orphans) * mem::size_of::<*mut T>()) +
+            (self.retired.capacity() * mem::size_of::<RefCell<Vec<*mut T>>>()) +
+            (self.registered.capacity() * mem::size_of::<sync::AtomicBool>())
+    }
+
+    /// Returns an error if the supplied thread or domain is out of range.
+    fn check(&self, thread: usize, domain: usize) -> Result<(), PointersError> {
+        let hazardous = self.hazardous.get(thread).ok_or(PointersError::Thread(thread))?;
+        if domain < hazardous.len() {
+            Ok(())
+        } else {
+            Err(PointersError::Domain(domain))
+        }
+    }
+
+    /// Returns the first domain for the supplied thread that is not set, or `None` if every domain
+    /// is set.
+    ///
+    /// The domain is not reserved, so it is only free until it is set using the supplied thread.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`domains`).
+    pub fn acquire_domain(&self, thread: usize) -> Option<usize> {
+        self.hazardous[thread].iter().position(|p| p.load(Relaxed).is_null())
+    }
+
+    /// Returns the current value of the supplied pointer without marking it as hazardous.
+    ///
+    /// **The returned pointer is not protected and may be deallocated at any time.** It must not be
+    /// dereferenced; it is only suitable for comparisons and heuristics. Use `mark` instead if the
+    /// pointer will be dereferenced.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn observe(&self, pointer: &AtomicPtr<T>) -> *mut T {
+        pointer.load(Acquire)
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread.
+    ///
+    /// The supplied pointer may store a tag in its low bits (the bits which are always zero due to
+    /// the alignment of `T`). The tagged value is stored and validated as a whole so a change to
+    /// only the tag is detected, but the tag is ignored by the default pointer identity (`Address`)
+    /// when determining whether a retired pointer is hazardous. Retired pointers must not be
+    /// tagged since they are deallocated as-is.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn mark(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: &AtomicPtr<T>
+    ) -> *mut T {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        loop {
+            let value = pointer.load(Acquire);
+            self.hazardous[thread][domain].store(value, Release);
+            // The sequentially consistent fence orders the store of the hazardous pointer before
+            // the load which validates it, pairing with the fence a reclamation issues before it
+            // loads the hazardous pointers (see `scan`).
+            fence(SeqCst);
+            if value == pointer.load(Acquire) {
+                return value;
+            }
+        }
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread to the value of
+    /// the supplied pointer and returns it, or clears the hazardous pointer and returns `None` if
+    /// the value is null.
+    ///
+    /// This is like `mark` except that a null value is returned immediately without being
+    /// validated, which makes checking whether a structure is empty cheaper. If the pointer
+    /// becomes non-null just after it is loaded, `None` is still returned; like any other failed
+    /// check for emptiness, the caller must retry if it needs to observe the new value.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn protect_optional(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: &AtomicPtr<T>
+    ) -> Option<*mut T> {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        let mut value = pointer.load(Acquire);
+        loop {
+            if value.is_null() {
+                self.hazardous[thread][domain].store(ptr::null_mut(), Release);
+                return None;
+            }
+
+            self.hazardous[thread][domain].store(value, Release);
+            fence(SeqCst);
+            let current = pointer.load(Acquire);
+            if value == current {
+                return Some(value);
+            }
+            value = current;
+        }
+    }
+
+    /// Protects a pointer and its successor with the supplied domains using the supplied thread
+    /// and returns them, or clears both domains and returns `None` if the first pointer is null.
+    ///
+    /// The value of `first` is protected with `first_domain`, then the value of the pointer that
+    /// `next` returns for the value it points to is protected with `second_domain`. Once both
+    /// are protected, `first` is checked again. If it has changed, both pointers are protected
+    /// again from the start, so the returned pointers were consistent (`first` pointed to the
+    /// first pointer and the first pointer's successor was the second pointer) at a single point
+    /// in time after both were protected. This is the snapshot of a predecessor and current node
+    /// needed to delete from a linked list. The second pointer may be null.
+    ///
+    /// `next` must return a pointer stored in the value it is supplied (e.g., its `next` field)
+    /// and must not have other side effects since it may be called several times. As with
+    /// `advance`, structures which remove nodes while they are being traversed must also check
+    /// that the first pointer has not been removed before relying on the second.
+    ///
+    /// ```
+    /// use std::ptr;
+    ///
+    /// use hazard::{AtomicPtr, BoxMemory, Memory, Pointers};
+    ///
+    /// struct Node {
+    ///     value: usize,
+    ///     next: AtomicPtr<Node>,
+    /// }
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 2, 8);
+    /// let second = BoxMemory.allocate(Node { value: 322, next: AtomicPtr::new(ptr::null_mut()) });
+    /// let first = BoxMemory.allocate(Node { value: 17, next: AtomicPtr::new(second) });
+    /// let head = AtomicPtr::new(first);
+    ///
+    /// let (a, b) = unsafe { pointers.protect_pair(0, 0, 1, &head, |n| &n.next) }.unwrap();
+    /// assert_eq!(unsafe { ((*a).value, (*b).value) }, (17, 322));
+    /// pointers.clear(0, 0);
+    /// pointers.clear(0, 1);
+    ///
+    /// pointers.retire(0, first);
+    /// pointers.retire(0, second);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The non-null values of `first` must point to valid values which are only deallocated by
+    /// being retired with this collection, since the first pointer is dereferenced to call `next`.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub unsafe fn protect_pair<F>(
+        &self,
+        thread: impl Into<Thread>,
+        first_domain: impl Into<Domain>,
+        second_domain: impl Into<Domain>,
+        first: &AtomicPtr<T>,
+        next: F,
+    ) -> Option<(*mut T, *mut T)> where F: Fn(&T) -> &AtomicPtr<T> {
+        let thread = thread.into().0;
+        let (first_domain, second_domain) = (first_domain.into().0, second_domain.into().0);
+        loop {
+            let a = match self.protect_optional(thread, first_domain, first) {
+                Some(a) => a,
+                None => {
+                    self.hazardous[thread][second_domain].store(ptr::null_mut(), Release);
+                    return None;
+                },
+            };
+            let b = self.mark(thread, second_domain, next(&*a));
+            if first.load(Acquire) == a {
+                return Some((a, b));
+            }
+        }
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread to the pointer
+    /// packed into the supplied word and returns the word along with the pointer.
+    ///
+    /// This is like `mark` for structures which pack a pointer and a count (or other tag) into a
+    /// single `AtomicUsize`. The supplied function extracts the pointer from a word; only that
+    /// pointer is stored as the hazardous pointer, but the whole word is validated so a change to
+    /// only the count is detected and the returned word can be used as the expected value of a
+    /// subsequent compare-and-swap.
+    ///
+    /// ```
+    /// use std::ptr;
+    /// use std::sync::atomic::Ordering::*;
+    ///
+    /// use hazard::{AtomicUsize, BoxMemory, Memory, Pointers};
+    ///
+    /// // The low six bits of a pointer to a node are always zero and store a count.
+    /// const COUNT: usize = 0b11_1111;
+    ///
+    /// #[repr(align(64))]
+    /// struct Node {
+    ///     value: usize,
+    ///     next: *mut Node,
+    /// }
+    ///
+    /// fn unpack(word: usize) -> *mut Node {
+    ///     (word & !COUNT) as *mut Node
+    /// }
+    ///
+    /// fn pack(node: *mut Node, previous: usize) -> usize {
+    ///     node as usize | (previous.wrapping_add(1) & COUNT)
+    /// }
+    ///
+    /// struct Stack {
+    ///     head: AtomicUsize,
+    ///     pointers: Pointers<Node, BoxMemory>,
+    /// }
+    ///
+    /// impl Stack {
+    ///     fn push(&self, value: usize) {
+    ///         let node = BoxMemory.allocate(Node { value, next: ptr::null_mut() });
+    ///         loop {
+    ///             let head = self.head.load(Acquire);
+    ///             unsafe { (*node).next = unpack(head); }
+    ///             if self.head.compare_exchange(head, pack(node, head), AcqRel, Acquire).is_ok() {
+    ///                 return;
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     fn pop(&self, thread: usize) -> Option<usize> {
+    ///         loop {
+    ///             let (head, node) = self.pointers.protect_tagged(thread, 0, &self.head, unpack);
+    ///             if node.is_null() {
+    ///                 self.pointers.clear(thread, 0);
+    ///                 return None;
+    ///             }
+    ///
+    ///             // The count changes on every push and pop, so this fails if the head was popped
+    ///             // and pushed again since it was protected even though the pointer is the same.
+    ///             let next = unsafe { (*node).next };
+    ///             if self.head.compare_exchange(head, pack(next, head), AcqRel, Acquire).is_ok() {
+    ///                 let value = unsafe { (*node).value };
+    ///                 self.pointers.clear(thread, 0);
+    ///                 self.pointers.retire(thread, node);
+    ///                 return Some(value);
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 1, 8);
+    /// let stack = Stack { head: AtomicUsize::new(0), pointers };
+    /// stack.push(17);
+    /// stack.push(322);
+    /// assert_eq!(stack.pop(0), Some(322));
+    /// assert_eq!(stack.pop(0), Some(17));
+    /// assert_eq!(stack.pop(0), None);
+    /// ```
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn protect_tagged<F>(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, word: &AtomicUsize, unpack: F
+    ) -> (usize, *mut T) where F: Fn(usize) -> *mut T {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        loop {
+            let value = word.load(Acquire);
+            let pointer = unpack(value);
+            self.hazardous[thread][domain].store(pointer, Release);
+            fence(SeqCst);
+            if value == word.load(Acquire) {
+                return (value, pointer);
+            }
+        }
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread or returns an
+    /// error if the supplied thread or domain is out of range.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn try_mark(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: &AtomicPtr<T>
+    ) -> Result<*mut T, PointersError> {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        self.check(thread, domain)?;
+        Ok(self.mark(thread, domain, pointer))
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread and returns a
+    /// token for the domain along with the marked pointer.
+    ///
+    /// Clearing the domain with `clear_slot` is equivalent to calling `clear` with the same thread
+    /// and domain, but the token ensures that the domain cleared is the domain that was marked.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn mark_slot(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: &AtomicPtr<T>
+    ) -> (Slot, *mut T) {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        (Slot { thread, domain }, self.mark(thread, domain, pointer))
+    }
+
+    /// Sets the first domain for the supplied thread that is not set to the supplied pointer and
+    /// returns that domain along with the marked pointer, or `None` if every domain is set.
+    ///
+    /// This combines `acquire_domain` and `mark` for readers which protect a varying number of
+    /// pointers. The caller is responsible for clearing the returned domain. If the marked pointer
+    /// is null, the domain is left unset and may be returned again.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn protect_indexed(
+        &self, thread: impl Into<Thread>, pointer: &AtomicPtr<T>
+    ) -> Option<(usize, *mut T)> {
+        let thread = thread.into().0;
+        let domain = self.acquire_domain(thread)?;
+        Some((domain, self.mark(thread, domain, pointer)))
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn mark_ptr(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: *mut T
+    ) -> *mut T {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        self.hazardous[thread][domain].store(pointer, Release);
+        fence(SeqCst);
+        pointer
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread and returns a
+    /// handle which clears it when dropped.
+    ///
+    /// The handle must be kept alive for as long as the pointer is used.
+    ///
+    /// ```
+    /// use hazard::{AtomicPtr, BoxMemory, Memory, Pointers};
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 1, 2);
+    /// let shared = AtomicPtr::new(BoxMemory.allocate(17));
+    ///
+    /// let hazard = pointers.protect(0, 0, &shared);
+    /// assert_eq!(unsafe { *hazard.as_ptr() }, 17);
+    /// drop(hazard);
+    ///
+    /// pointers.retire(0, shared.into_inner());
+    /// ```
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn protect(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: &AtomicPtr<T>
+    ) -> Hazard<'_, T, M, I> {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        let pointer = self.mark(thread, domain, pointer);
+        Hazard { pointers: self, thread, domain, pointer }
+    }
+
+    /// Returns an empty set of hazardous pointers for the supplied thread.
+    ///
+    /// See `HazardSet`.
+    pub fn hazard_set(&self, thread: impl Into<Thread>) -> HazardSet<'_, T, M, I> {
+        HazardSet { pointers: self, thread: thread.into().0, hazards: vec![] }
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread and returns
+    /// whether the supplied validation function considers the pointer to still be valid.
+    ///
+    /// The validation function is called once after the hazardous pointer is set. If it returns
+    /// `false`, the pointer may have been retired before it was protected so the caller must either
+    /// try again or clear the hazardous pointer.
+    ///
+    /// **Forward progress guarantee:** that of the validation function.
+    pub fn protect_validated<F>(
+        &self,
+        thread: impl Into<Thread>,
+        domain: impl Into<Domain>,
+        pointer: *mut T,
+        validate: F,
+    ) -> bool where F: FnOnce(*mut T) -> bool {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        self.hazardous[thread][domain].store(pointer, Release);
+        fence(SeqCst);
+        validate(pointer)
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread to the supplied
+    /// pointer previously loaded from the supplied pointer with relaxed ordering and returns it if
+    /// the supplied pointer still has that value, or clears the hazardous pointer and returns
+    /// `None` otherwise.
+    ///
+    /// This lets a reader load a pointer with relaxed ordering on a fast path (e.g., to check
+    /// whether a structure is empty) and only pay for ordering once it decides to use the value.
+    /// The hazardous pointer is stored with release ordering and fenced and the supplied pointer is
+    /// then reloaded with acquire ordering, exactly as in `mark`. An acquire fence is then issued,
+    /// which synchronizes with the release store that published the observed pointer, so the
+    /// value it points to may be read as if the observed pointer had been loaded with acquire
+    /// ordering. If `None` is returned, the caller should load the pointer again and retry.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn protect_after_relaxed(
+        &self,
+        thread: impl Into<Thread>,
+        domain: impl Into<Domain>,
+        observed: *mut T,
+        pointer: &AtomicPtr<T>,
+    ) -> Option<*mut T> {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        self.hazardous[thread][domain].store(observed, Release);
+        fence(SeqCst);
+        let current = pointer.load(Acquire);
+        fence(Acquire);
+        if current == observed {
+            Some(observed)
+        } else {
+            self.hazardous[thread][domain].store(ptr::null_mut(), Release);
+            None
+        }
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread and returns the
+    /// pointer that was previously stored for that domain.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn mark_ptr_prev(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: *mut T
+    ) -> *mut T {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        let previous = self.hazardous[thread][domain].swap(pointer, AcqRel);
+        fence(SeqCst);
+        previous
+    }
+
+    /// Sets the hazardous pointer for the supplied domain using the supplied thread to the pointer
+    /// loaded from the supplied successor of the supplied protected pointer and returns it.
+    ///
+    /// This is one step of a hand-over-hand traversal of a linked structure: `current` must be
+    /// protected by another domain of the supplied thread and `next` must be stored in the value
+    /// `current` points to (e.g., its `next` field). The loaded pointer is validated by reloading
+    /// `next` after it has been protected, which is only sufficient if `current` was still
+    /// reachable from the structure at that point; **structures which remove nodes while they are
+    /// being traversed must also check that `current` has not been removed** (e.g., with a deletion
+    /// mark) before relying on the returned pointer. The protection of `current` is left for the
+    /// caller to clear or to reuse for the next step.
+    ///
+    /// ```
+    /// use std::ptr;
+    /// use std::sync::atomic::Ordering::*;
+    ///
+    /// use hazard::{AtomicPtr, BoxMemory, Memory, Pointers};
+    ///
+    /// struct Node {
+    ///     value: usize,
+    ///     next: AtomicPtr<Node>,
+    /// }
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 2, 8);
+    /// let head = (1..4).rev().fold(ptr::null_mut(), |next, value| {
+    ///     BoxMemory.allocate(Node { value, next: AtomicPtr::new(next) })
+    /// });
+    /// let head = AtomicPtr::new(head);
+    ///
+    /// let mut values = vec![];
+    /// let (mut current, mut domain) = (pointers.mark(0, 0, &head), 1);
+    /// while !current.is_null() {
+    ///     values.push(unsafe { (*current).value });
+    ///     current = pointers.advance(0, domain, current, unsafe { &(*current).next });
+    ///     domain = 1 - domain;
+    /// }
+    /// pointers.clear(0, 0);
+    /// pointers.clear(0, 1);
+    /// assert_eq!(values, [1, 2, 3]);
+    ///
+    /// let mut node = head.into_inner();
+    /// while !node.is_null() {
+    ///     let next = unsafe { (*node).next.load(Relaxed) };
+    ///     pointers.retire(0, node);
+    ///     node = next;
+    /// }
+    /// ```
+    ///
+    /// If debug assertions are enabled, this panics if `current` is not protected by another
+    /// domain of the supplied thread.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn advance(
+        &self,
+        thread: impl Into<Thread>,
+        domain: impl Into<Domain>,
+        current: *mut T,
+        next: &AtomicPtr<T>,
+    ) -> *mut T {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        debug_assert!(
+            self.hazardous[thread].iter().enumerate()
+                .any(|(d, h)| d != domain && h.load(Relaxed) == current),
+            "advanced from {:?} which is not protected by another domain of thread {}",
+            current,
+            thread,
+        );
+        self.mark(thread, domain, next)
+    }
+
+    /// Clears the hazardous pointer for the supplied domain using the supplied thread.
+    ///
+    /// If this collection was built with `PointersBuilder::reclaim_on_clear`, this also reclaims
+    /// some of the retired pointers for the supplied thread.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious (that of the reclaimer if
+    /// `PointersBuilder::reclaim_on_clear` was used).
+    pub fn clear(&self, thread: impl Into<Thread>, domain: impl Into<Domain>) {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        self.hazardous[thread][domain].store(ptr::null_mut(), Release);
+        if self.reclaim_on_clear != 0 {
+            self.reclaim_bounded(thread, self.reclaim_on_clear);
+        }
+    }
+
+    /// Sets each hazardous pointer for the supplied thread to the value of the supplied pointer
+    /// with the same index and returns the marked pointers.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the number of supplied pointers is not the number of domains of the
+    /// supplied thread.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn mark_all_from<const N: usize>(
+        &self, thread: impl Into<Thread>, pointers: &[&AtomicPtr<T>; N]
+    ) -> [*mut T; N] {
+        let thread = thread.into().0;
+        assert_eq!(N, self.hazardous[thread].len(), "expected one pointer per domain");
+        array::from_fn(|domain| self.mark(thread, domain, pointers[domain]))
+    }
+
+    /// Clears every hazardous pointer for the supplied thread.
+    ///
+    /// Like `clear`, this also reclaims some of the retired pointers for the supplied thread if
+    /// this collection was built with `PointersBuilder::reclaim_on_clear` (once, not per domain).
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`domains`) (that of the reclaimer if
+    /// `PointersBuilder::reclaim_on_clear` was used).
+    pub fn clear_all(&self, thread: impl Into<Thread>) {
+        let thread = thread.into().0;
+        for hazardous in &self.hazardous[thread] {
+            hazardous.store(ptr::null_mut(), Release);
+        }
+        if self.reclaim_on_clear != 0 {
+            self.reclaim_bounded(thread, self.reclaim_on_clear);
+        }
+    }
+
+    /// Reclaims no more than the supplied number of the oldest retired pointers for the supplied
+    /// thread with the reclaimer.
+    ///
+    /// Nothing is reclaimed if the retired pointers for the supplied thread are already borrowed.
+    fn reclaim_bounded(&self, thread: usize, limit: usize) {
+        if let Ok(mut retired) = self.retired[thread].try_borrow_mut() {
+            self.adopt(thread, &mut retired);
+            let end = retired.len().min(limit);
+            self.sweep_window(thread, &mut retired, 0..end);
+        }
+    }
+
+    /// Clears the first domain for the supplied thread that is set to the supplied pointer and
+    /// returns whether any domain was set to the supplied pointer.
+    ///
+    /// If more than one domain is set to the supplied pointer, only the first is cleared.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`domains`).
+    pub fn clear_ptr(&self, thread: usize, pointer: *mut T) -> bool {
+        self.hazardous[thread].iter().any(|p| {
+            p.compare_exchange(pointer, ptr::null_mut(), Release, Relaxed).is_ok()
+        })
+    }
+
+    /// Clears the hazardous pointer for the domain of the supplied token.
+    ///
+    /// See `mark_slot`.
+    ///
+    /// **Forward progress guarantee:** that of `clear`.
+    pub fn clear_slot(&self, slot: Slot) {
+        self.clear(slot.thread, slot.domain);
+    }
+
+    /// Clears the hazardous pointer for the supplied domain using the supplied thread or returns
+    /// an error if the supplied thread or domain is out of range.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn try_clear(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>
+    ) -> Result<(), PointersError> {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        self.check(thread, domain)?;
+        self.clear(thread, domain);
+        Ok(())
+    }
+
+    /// Returns whether the supplied pointer is considered hazardous.
+    ///
+    /// Pointers are compared using the pointer identity of this collection.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains`).
+    pub fn hazardous(&self, pointer: *mut T) -> bool {
+        self.scan(pointer, true) != 0
+    }
+
+    /// Returns the number of domains across every thread that the supplied pointer is set for.
+    ///
+    /// Pointers are compared using the pointer identity of this collection.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains`).
+    pub fn hazardous_refs(&self, pointer: *mut T) -> usize {
+        self.scan(pointer, false)
+    }
+
+    /// Returns the number of hazardous pointers which protect the supplied pointer, stopping at the
+    /// first if `first` is `true`.
+    fn scan(&self, pointer: *mut T, first: bool) -> usize {
+        let range = I::range(pointer);
+        let (start, end) = (range.start, range.end);
+        // The sequentially consistent fence orders the removal of the supplied pointer from any
+        // shared structure before the loads of the hazardous pointers. The loads are relaxed so
+        // each thread's row can be counted without branching, and the acquire fence afterwards
+        // synchronizes with the releasing stores of any loaded pointers (e.g., `clear`).
+        fence(SeqCst);
+        let skip = if self.skip_own && self.layout != RetiredLayout::Stack {
+            self.sweeping()
+        } else {
+            None
+        };
+        let mut count = 0;
+        for (thread, hazardous) in self.hazardous.iter().enumerate() {
+            if skip == Some(thread) {
+                continue;
+            }
+
+            count += hazardous.iter().map(|h| {
+                let address = I::address(h.load(Relaxed));
+                (address >= start && address < end) as usize
+            }).sum::<usize>();
+            if first && count != 0 {
+                break;
+            }
+        }
+        fence(Acquire);
+        count
+    }
+
+    /// Deallocates the supplied retired pointer if it is not hazardous and returns whether it was
+    /// deallocated.
+    ///
+    /// # Safety
+    ///
+    /// The supplied pointer must be a retired pointer and, if it is deallocated, it must be removed
+    /// from the list of retired pointers it belongs to.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains`).
+    pub unsafe fn kill(&self, pointer: *mut T) -> bool {
+        if self.hazardous(pointer) {
+            false
+        } else {
+            self.free(pointer);
+            true
+        }
+    }
+
+    /// Deallocates the supplied pointer, first overwriting the value it points to with `POISON` if
+    /// debug assertions are enabled and `T` does not need to be dropped.
+    ///
+    /// Values that need to be dropped are not poisoned since they are dropped by `Memory`.
+    unsafe fn free(&self, pointer: *mut T) {
+        poison(pointer);
+        self.memory.deallocate(pointer);
+    }
+
+    /// Deallocates the supplied pointers, first poisoning them like `free`.
+    unsafe fn free_batch(&self, pointers: &[*mut T]) {
+        for pointer in pointers {
+            poison(*pointer);
+        }
+        self.memory.deallocate_batch(pointers);
+    }
+
+    /// Drops the value of the supplied pointer retired with `retire_layout` and deallocates it
+    /// with the layout and function it was retired with.
+    unsafe fn free_layout(&self, (pointer, layout, deallocate): Layouted<T, M>) {
+        ptr::drop_in_place(pointer);
+        deallocate(&self.memory, pointer as *mut u8, layout);
+    }
+
+    /// Returns an iterator over the non-null hazardous pointers of every thread.
+    ///
+    /// Each hazardous pointer is loaded as the iterator reaches it so the iterator does not
+    /// represent a consistent view of the hazardous pointers.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains`).
+    pub fn hazardous_iter(&self) -> impl Iterator<Item=*mut T> + '_ {
+        self.hazardous.iter()
+            .flat_map(|h| h.iter().map(|p| p.load(Acquire)))
+            .filter(|p| !p.is_null())
+    }
+
+    /// Returns the hazardous pointers of the supplied thread indexed by domain.
+    ///
+    /// Unlike `hazardous_iter`, domains which are not set are included as null pointers. This is
+    /// intended for debugging and testing (e.g., asserting that a reader protected the expected
+    /// pointers).
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`domains`).
+    pub fn hazards_of(&self, thread: usize) -> Vec<*mut T> {
+        self.hazardous[thread].iter().map(|p| p.load(Acquire)).collect()
+    }
+
+    /// Returns the non-null hazardous pointers of every thread.
+    ///
+    /// Unlike `hazardous_iter`, every hazardous pointer is loaded before this method returns so the
+    /// returned pointers do not change as they are examined. The pointers are still loaded one at
+    /// a time, so the snapshot may include a pointer that a thread has already moved to another
+    /// domain or miss one that it has moved to a domain that was already loaded.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains`).
+    pub fn hazardous_snapshot(&self) -> Vec<*mut T> {
+        self.hazardous_iter().collect()
+    }
+
+    /// Returns the addresses protected by the non-null hazardous pointers of every thread, sorted.
+    ///
+    /// Like `scan`, this issues a sequentially consistent fence before loading the hazardous
+    /// pointers.
+    fn snapshot(&self) -> Vec<usize> {
+        fence(SeqCst);
+        let mut snapshot = self.hazardous_snapshot().into_iter().map(I::address)
+            .collect::<Vec<_>>();
+        snapshot.sort_unstable();
+        snapshot
+    }
+
+    /// Reclaims the supplied retired pointers of the supplied thread with the reclaimer.
+    ///
+    /// Nothing is reclaimed if the supplied thread or every thread is pinned.
+    fn sweep(&self, thread: usize, retired: &mut Vec<*mut T>) -> ReclaimReport {
+        self.adopt(thread, retired);
+        let end = retired.len();
+        let report = self.sweep_window(thread, retired, 0..end);
+        let layouts = self.sweep_layouts(thread);
+        ReclaimReport {
+            freed: report.freed + layouts.freed,
+            retained: report.retained + layouts.retained,
+        }
+    }
+
+    /// Reclaims the supplied range of the supplied retired pointers of the supplied thread with
+    /// the reclaimer, records the sweep, and notifies the observer.
+    ///
+    /// Nothing is reclaimed if the supplied thread or every thread is pinned.
+    fn sweep_window(
+        &self, thread: usize, retired: &mut Vec<*mut T>, range: ops::Range<usize>
+    ) -> ReclaimReport {
+        if self.pinned(thread) {
+            let report = ReclaimReport { freed: 0, retained: retired.len() };
+            self.restack(thread, retired);
+            return report;
+        }
+
+        #[cfg(feature="tracing")]
+        let _span = tracing::debug_span!("sweep", thread, retired = retired.len()).entered();
+        #[cfg(feature="timing")]
+        let start = Instant::now();
+        let before = retired.len();
+        let previous = SWEEPING.with(|s| s.replace((self as *const Self as usize, thread)));
+        if range.start == 0 && range.end == retired.len() {
+            self.reclaim_ordered(retired);
+        } else {
+            let mut window = retired.drain(range.clone()).collect::<Vec<_>>();
+            self.reclaim_ordered(&mut window);
+            retired.splice(range.start..range.start, window);
+        }
+        SWEEPING.with(|s| s.set(previous));
+        let freed = before - retired.len();
+        self.undefer(thread, retired);
+        let report = ReclaimReport { freed, retained: retired.len() };
+        self.restack(thread, retired);
+        self.record(thread, report);
+        #[cfg(feature="timing")]
+        if let Some(ref observer) = self.observer {
+            observer.reclaimed_in(thread, start.elapsed(), report);
+        }
+        report
+    }
+
+    /// Reclaims the supplied retired pointers with the reclaimer in the free order.
+    fn reclaim_ordered(&self, retired: &mut Vec<*mut T>) {
+        if self.order == FreeOrder::Lifo {
+            retired.reverse();
+            self.reclaimer.reclaim(self, retired);
+            retired.reverse();
+        } else {
+            self.reclaimer.reclaim(self, retired);
+        }
+    }
+
+    /// Returns the thread whose retired pointers are being reclaimed by this collection on the
+    /// current thread, if any (`usize::MAX` for pointers that are not only retired by the user of
+    /// a thread, in which case no domains are skipped).
+    fn sweeping(&self) -> Option<usize> {
+        let (pointers, thread) = SWEEPING.with(|s| s.get());
+        if pointers == self as *const Self as usize { Some(thread) } else { None }
+    }
+
+    /// Records the supplied report of a sweep of the retired pointers of the supplied thread and
+    /// notifies the observer.
+    fn record(&self, thread: usize, report: ReclaimReport) {
+        self.last[thread].store(report.freed, Relaxed);
+        self.tally(thread, report.retained, 0, report.freed);
+        self.notify_reclaimed(thread, report);
+    }
+
+    /// Notifies the observer that the supplied pointer was retired using the supplied thread and
+    /// emits a `tracing` event if the `tracing` feature is enabled.
+    fn notify_retired(&self, thread: usize, pointer: *mut T, pending: usize) {
+        #[cfg(feature="tracing")]
+        tracing::trace!(thread, pending, "retired {:?}", pointer);
+        if let Some(ref observer) = self.observer {
+            observer.retired(thread, pointer, pending);
+        }
+    }
+
+    /// Notifies the observer that the retired pointers of the supplied thread were reclaimed and
+    /// emits a `tracing` event if the `tracing` feature is enabled.
+    fn notify_reclaimed(&self, thread: usize, report: ReclaimReport) {
+        #[cfg(feature="tracing")]
+        if report.freed == 0 && report.retained != 0 {
+            tracing::debug!(
+                thread,
+                retained = report.retained,
+                "reclaimed nothing, every retired pointer is still hazardous",
+            );
+        } else {
+            tracing::debug!(thread, freed = report.freed, retained = report.retained, "reclaimed");
+        }
+        if let Some(ref observer) = self.observer {
+            observer.reclaimed(thread, report);
+        }
+    }
+
+    /// Updates the reclamation metrics of the supplied thread with the supplied number of pending
+    /// pointers and the supplied numbers of newly retired and deallocated pointers.
+    fn tally(&self, thread: usize, pending: usize, retired: usize, freed: usize) {
+        let tally = &self.tallies[thread];
+        tally.pending.store(pending, Relaxed);
+        tally.high_water.fetch_max(pending, Relaxed);
+        tally.retired.fetch_add(retired, Relaxed);
+        tally.freed.fetch_add(freed, Relaxed);
+    }
+
+    /// Returns a snapshot of the reclamation metrics of the supplied thread.
+    ///
+    /// Unlike `pending`, this does not borrow the retired pointers of the supplied thread, so it
+    /// may be called from any thread (e.g., a monitoring thread) at any time, even while the
+    /// supplied thread is retiring or reclaiming pointers. The metrics are mirrored into atomics
+    /// after each operation on the retired pointers, so they lag the true retired pointers by at
+    /// most one operation and the fields may be updated independently of one another.
+    /// Pointers retired with `retire_intrusive` or orphaned are not included.
+    ///
+    /// ```
+    /// use hazard::{BoxMemory, Memory, Pointers};
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 1, 4);
+    /// for value in 0..6 {
+    ///     pointers.retire(0, BoxMemory.allocate(value));
+    /// }
+    /// let metrics = pointers.metrics(0);
+    /// assert_eq!((metrics.pending, metrics.high_water), (2, 4));
+    /// assert_eq!((metrics.retired, metrics.freed), (6, 4));
+    /// ```
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn metrics(&self, thread: usize) -> Metrics {
+        let tally = &self.tallies[thread];
+        Metrics {
+            pending: tally.pending.load(Relaxed),
+            high_water: tally.high_water.load(Relaxed),
+            retired: tally.retired.load(Relaxed),
+            freed: tally.freed.load(Relaxed),
+        }
+    }
+
+    /// Returns the threshold at which a list of retired pointers is reclaimed.
+    ///
+    /// The threshold is only a heuristic and doesn't order any other memory accesses, so it is
+    /// loaded with relaxed ordering to keep retiring pointers as cheap as with a constant.
+    fn threshold(&self) -> usize {
+        match self.policy {
+            ThresholdPolicy::Fixed => self.threshold.load(Relaxed),
+            ThresholdPolicy::Scaled { per_thread } => {
+                let threads = if self.dynamic.load(Relaxed) {
+                    self.active.load(Relaxed).max(1)
+                } else {
+                    self.retired.len()
+                };
+                per_thread.saturating_mul(threads)
+            },
+        }
+    }
+
+    /// Returns an estimate of the cost of reclaiming the retired pointers for the supplied thread.
+    ///
+    /// This is the number of retired pointers for the supplied thread (as reported by `metrics`)
+    /// multiplied by the total number of hazardous pointers, which is an upper bound on the number
+    /// of comparisons made by `NaiveScan` and a rough measure of the work done by the other
+    /// reclaimers. It is a heuristic computed from maintained counters, not a measured time, so it
+    /// is cheap enough for a scheduler to call before deciding whether to defer reclamation (e.g.,
+    /// until the thread is idle).
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn estimate_reclaim_cost(&self, thread: usize) -> usize {
+        self.tallies[thread].pending.load(Relaxed).saturating_mul(self.slots)
+    }
+
+    /// Returns the number of pointers deallocated by the most recent reclamation of the retired
+    /// pointers for the supplied thread, or `None` if they have never been reclaimed.
+    ///
+    /// Repeated zeros suggest that the retired pointers are stuck behind long-lived hazardous
+    /// pointers and that retiring more pointers will only use more memory.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn last_sweep_freed(&self, thread: usize) -> Option<usize> {
+        match self.last[thread].load(Relaxed) {
+            usize::MAX => None,
+            freed => Some(freed),
+        }
+    }
+
+    /// Deallocates the retired pointers in the supplied list that are not in the supplied snapshot.
+    ///
+    /// Nothing is reclaimed if every thread is pinned.
+    fn reclaim_snapshot(&self, snapshot: &[usize], retired: &mut Vec<*mut T>) -> ReclaimReport {
+        fence(SeqCst);
+        if self.pins.load(SeqCst) != 0 {
+            return ReclaimReport { freed: 0, retained: retired.len() };
+        }
+
+        let (retained, mut freed) = retired.drain(..).partition::<Vec<_>, _>(|p| {
+            let range = I::range(*p);
+            let index = snapshot.partition_point(|a| *a < range.start);
+            index < snapshot.len() && snapshot[index] < range.end
+        });
+        if self.order == FreeOrder::Lifo {
+            freed.reverse();
+        }
+        unsafe { self.free_batch(&freed); }
+        *retired = retained;
+        ReclaimReport { freed: freed.len(), retained: retired.len() }
+    }
+
+    /// Returns the number of retired pointers for the supplied thread that have not yet been
+    /// reclaimed.
+    ///
+    /// This includes the pointers on the lock-free stack of the supplied thread (see
+    /// `retire_remote` and `RetiredLayout::Stack`), the pointers retired with `retire_layout`, and
+    /// the pointers retired while its retired pointers are being reclaimed, but not pointers
+    /// retired with `retire_intrusive`. This
+    /// borrows the retired pointers of the supplied thread, see `metrics` for a snapshot that may
+    /// be read from any thread.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn pending(&self, thread: usize) -> usize {
+        self.retired[thread].borrow().len() + self.stacked[thread].load(Relaxed) +
+            self.deferred_len(thread) + self.layouts[thread].borrow().len()
+    }
+
+    /// Calls the supplied function with each retired pointer for the supplied thread that has not
+    /// yet been reclaimed, oldest first, without removing them.
+    ///
+    /// The pointers on the lock-free stack of the supplied thread are first moved into its retired
+    /// pointers, newest first.
+    ///
+    /// This is only for inspection (e.g., dumping the retired pointers which are stuck behind
+    /// long-lived hazardous pointers); the supplied function must not deallocate the pointers.
+    ///
+    /// # Panics
+    ///
+    /// This borrows the retired pointers for the supplied thread, so this panics if the supplied
+    /// function retires or reclaims pointers using the supplied thread.
+    ///
+    /// **Forward progress guarantee:** that of the supplied function.
+    pub fn for_each_retired(&self, thread: usize, mut f: impl FnMut(*mut T)) {
+        let mut retired = self.retired[thread].borrow_mut();
+        self.adopt(thread, &mut retired);
+        for pointer in &*retired {
+            f(*pointer);
+        }
+        self.restack(thread, &mut retired);
+    }
+
+    /// Returns whether the supplied pointer has been retired using any thread (or orphaned) and
+    /// has not yet been reclaimed.
+    ///
+    /// This is a diagnostic for checking the invariants of structures in tests. It checks every
+    /// retired pointer, including the pointers on the lock-free stack of each thread (which are
+    /// first moved into the retired pointers of the thread) and the pointers retired while the
+    /// retired pointers of a thread are being reclaimed, but not pointers retired with
+    /// `retire_intrusive`. This method borrows the retired list of every thread so it must not be
+    /// called while any thread is retiring pointers.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn contains_retired(&self, pointer: *mut T) -> bool {
+        self.retired.iter().enumerate().any(|(thread, retired)| {
+            let mut retired = retired.borrow_mut();
+            self.adopt(thread, &mut retired);
+            let contains = retired.contains(&pointer) || {
+                let deferred = self.deferred[thread].take();
+                let contains = deferred.contains(&pointer);
+                self.deferred[thread].set(deferred);
+                contains
+            } || self.layouts[thread].borrow().iter().any(|l| l.0 == pointer);
+            self.restack(thread, &mut retired);
+            contains
+        }) || self.orphans().contains(&pointer)
+    }
+
+    /// Deallocates the retired pointers for the supplied thread that are not hazardous regardless
+    /// of whether the threshold has been reached.
+    ///
+    /// If this collection was built with `RetiredLayout::Global`, this also reclaims the shared
+    /// list of retired pointers.
+    ///
+    /// **Forward progress guarantee:** that of the reclaimer (blocking if this collection was
+    /// built with `RetiredLayout::Global`).
+    pub fn reclaim(&self, thread: usize) -> ReclaimReport {
+        let report = self.sweep(thread, &mut self.retired[thread].borrow_mut());
+        if self.layout == RetiredLayout::Global {
+            let orphans = self.reclaim_orphans();
+            ReclaimReport {
+                freed: report.freed + orphans.freed,
+                retained: report.retained + orphans.retained,
+            }
+        } else {
+            report
+        }
+    }
+
+    /// Deallocates the retired pointers for the supplied thread that are not hazardous if the
+    /// threshold has been reached and returns the number of pointers that were deallocated.
+    ///
+    /// This is a cheap way for a thread to catch up on reclamation at a convenient time (e.g.,
+    /// when an event loop is idle) without retiring another pointer.
+    ///
+    /// **Forward progress guarantee:** that of the reclaimer.
+    pub fn reclaim_if_needed(&self, thread: usize) -> usize {
+        let mut retired = self.retired[thread].borrow_mut();
+        if retired.len() + self.stacked[thread].load(Relaxed) >= self.threshold() {
+            self.sweep(thread, &mut retired).freed
+        } else {
+            0
+        }
+    }
+
+    /// Deallocates the non-hazardous pointers among no more than the remaining number of retired
+    /// pointers for the supplied thread allowed by the supplied budget and decrements the budget
+    /// by the number of retired pointers checked.
+    ///
+    /// Successive calls for the same thread resume from where the previous call stopped (wrapping
+    /// around to the oldest retired pointers) so every retired pointer is eventually checked even
+    /// if the budget is small. Like `reclaim_on_clear`, the checked retired pointers are reclaimed
+    /// with the reclaimer in the free order.
+    ///
+    /// ```
+    /// use hazard::{BoxMemory, Memory, Pointers, ReclaimBudget};
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 1, usize::MAX);
+    /// for value in 0..5 {
+    ///     pointers.retire(0, BoxMemory.allocate(value));
+    /// }
+    ///
+    /// let mut budget = ReclaimBudget::new(3);
+    /// assert_eq!(pointers.reclaim_with_budget(0, &mut budget).freed, 3);
+    /// assert!(budget.is_exhausted());
+    ///
+    /// budget.remaining = 3;
+    /// assert_eq!(pointers.reclaim_with_budget(0, &mut budget).freed, 2);
+    /// assert_eq!(budget.remaining, 1);
+    /// ```
+    ///
+    /// **Forward progress guarantee:** that of the reclaimer.
+    pub fn reclaim_with_budget(&self, thread: usize, budget: &mut ReclaimBudget) -> ReclaimReport {
+        let mut retired = self.retired[thread].borrow_mut();
+        if self.pinned(thread) || budget.is_exhausted() {
+            return ReclaimReport { freed: 0, retained: retired.len() };
+        }
+
+        self.adopt(thread, &mut retired);
+        let mut offset = self.offsets[thread].load(Relaxed);
+        if offset >= retired.len() {
+            offset = 0;
+        }
+        let end = retired.len().min(offset.saturating_add(budget.remaining));
+        budget.remaining -= end - offset;
+        let report = self.sweep_window(thread, &mut retired, offset..end);
+        self.offsets[thread].store(end - report.freed, Relaxed);
+        report
+    }
+
+    /// Deallocates the retired pointers for the supplied thread that are not hazardous and removes
+    /// and returns the rest, leaving the retired pointers for the supplied thread empty.
+    ///
+    /// The returned pointers are still hazardous so they are not deallocated; the caller is
+    /// responsible for retiring them again later (e.g., once the threads protecting them have
+    /// finished) or otherwise deallocating them once they are no longer hazardous. This suits
+    /// shutting down a subsystem while other subsystems keep running.
+    ///
+    /// **Forward progress guarantee:** that of the reclaimer.
+    pub fn drain(&self, thread: usize) -> Vec<*mut T> {
+        let mut retired = self.retired[thread].borrow_mut();
+        self.sweep(thread, &mut retired);
+        self.adopt(thread, &mut retired);
+        self.tally(thread, 0, 0, 0);
+        mem::take(&mut *retired)
+    }
+
+    /// Removes and returns the retired pointers for the supplied thread that are not hazardous.
+    ///
+    /// The returned pointers are not deallocated; the caller is responsible for deallocating them.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains * retired`).
+    pub fn drain_reclaimable(&self, thread: usize) -> Vec<*mut T> {
+        let mut retired = self.retired[thread].borrow_mut();
+        self.adopt(thread, &mut retired);
+        let (retained, reclaimable) = retired.drain(..)
+            .partition::<Vec<_>, _>(|p| self.hazardous(*p));
+        self.tally(thread, retained.len(), 0, 0);
+        *retired = retained;
+        self.restack(thread, &mut retired);
+        reclaimable
+    }
+
+    /// Returns whether no hazardous pointers are set and no retired pointers are waiting to be
+    /// reclaimed.
+    ///
+    /// Every list of retired pointers is considered, including the lock-free stack of each thread
+    /// and the pointers retired while the retired pointers of a thread are being reclaimed. This
+    /// is a snapshot; other threads may set hazardous pointers or retire pointers before this
+    /// method returns. This method borrows the retired list of every thread so it must not be
+    /// called while any thread is retiring pointers.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn is_quiescent(&self) -> bool {
+        self.hazardous_iter().next().is_none() &&
+            self.retired.iter().all(|r| r.borrow().is_empty()) &&
+            self.remote.iter().all(|r| r.load(Acquire).is_null()) &&
+            (0..self.deferred.len()).all(|t| self.deferred_len(t) == 0) &&
+            self.layouts.iter().all(|l| l.borrow().is_empty()) &&
+            self.intrusive.iter().all(|i| i.get().0.is_null()) &&
+            self.orphans().is_empty()
+    }
+
+    /// Reclaims retired pointers until this collection is quiescent (see `is_quiescent`) or the
+    /// supplied timeout elapses and returns whether this collection became quiescent.
+    ///
+    /// This assumes the other threads will eventually clear their hazardous pointers; if no
+    /// timeout is supplied and a hazardous pointer is never cleared, this never returns. Between
+    /// attempts, the current thread sleeps for exponentially increasing intervals (up to 10
+    /// milliseconds). Like `reclaim_all`, this borrows the retired list of every thread so it must
+    /// not be called while any thread is retiring pointers (e.g., only once the other threads
+    /// have stopped retiring pointers during shutdown).
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn wait_quiescent(&self, timeout: Option<Duration>) -> bool {
+        let start = Instant::now();
+        let mut backoff = Duration::from_micros(1);
+        loop {
+            self.reclaim_all();
+            if self.is_quiescent() {
+                return true;
+            }
+
+            if let Some(timeout) = timeout {
+                let elapsed = start.elapsed();
+                if elapsed >= timeout {
+                    return false;
+                }
+                backoff = backoff.min(timeout - elapsed);
+            }
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(Duration::from_millis(10));
+        }
+    }
+
+    /// Returns a future which reclaims retired pointers until this collection is quiescent (see
+    /// `is_quiescent`).
+    ///
+    /// This is the asynchronous counterpart of `wait_quiescent` for shutting down on an executor
+    /// thread. The future reclaims every retired pointer it can (see `reclaim_all`), and while
+    /// the collection is not yet quiescent it awaits the future returned by calling the supplied
+    /// sleep function with the supplied interval (e.g., the timer of the runtime) before trying
+    /// again, rather than blocking the executor. It has the same restrictions as
+    /// `wait_quiescent`.
+    ///
+    /// This method is only available if the `async` feature is enabled.
+    ///
+    /// ```
+    /// use std::future::{self, Future};
+    /// use std::pin::{pin};
+    /// use std::sync::{Arc};
+    /// use std::task::{Context, Poll, Wake, Waker};
+    /// use std::time::{Duration};
+    ///
+    /// use hazard::{BoxMemory, Memory, Pointers};
+    ///
+    /// struct Ignore;
+    ///
+    /// impl Wake for Ignore {
+    ///     fn wake(self: Arc<Self>) { }
+    /// }
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 1, 8);
+    /// pointers.retire(0, BoxMemory.allocate(17));
+    ///
+    /// // With a runtime, the sleep function would return its timer (e.g., `tokio::time::sleep`).
+    /// let interval = Duration::from_millis(10);
+    /// let mut future = pin!(pointers.reclaim_until_quiescent(interval, |_| future::ready(())));
+    /// let waker = Waker::from(Arc::new(Ignore));
+    /// let mut context = Context::from_waker(&waker);
+    /// assert_eq!(future.as_mut().poll(&mut context), Poll::Ready(()));
+    /// ```
+    ///
+    /// **Forward progress guarantee:** blocking.
+    #[cfg(feature="async")]
+    pub fn reclaim_until_quiescent<'p, S, F>(
+        &'p self, interval: Duration, sleep: S
+    ) -> impl Future<Output=()> + 'p
+    where S: 'p + FnMut(Duration) -> F, F: 'p + Future<Output=()> {
+        Quiescence { pointers: self, interval, sleep, timer: None }
+    }
+
+    /// Returns an iterator over views of each thread.
+    pub fn threads_iter(&self) -> impl Iterator<Item=ThreadView<'_, T, M, I>> {
+        (0..self.retired.len()).map(move |thread| ThreadView { pointers: self, thread })
+    }
+
+    /// Deallocates the orphaned pointers that are not hazardous.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn reclaim_orphans(&self) -> ReclaimReport {
+        let snapshot = self.snapshot();
+        self.sweep_shared(&snapshot)
+    }
+
+    /// Deallocates the non-hazardous retired pointers of every thread (including those in the
+    /// intrusive lists of retired pointers) and the non-hazardous orphaned pointers.
+    ///
+    /// The hazardous pointers are read once at the start of the call and that snapshot is used for
+    /// every retired list other than the intrusive lists, so this is cheaper than reclaiming each
+    /// thread individually.
+    ///
+    /// This method borrows the retired list of every thread so it must not be called while any
+    /// thread is retiring pointers (e.g., only while the other threads are paused).
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn reclaim_all(&self) -> ReclaimReport {
+        let snapshot = self.snapshot();
+        let mut report = self.sweep_shared(&snapshot);
+        for (thread, retired) in self.retired.iter().enumerate() {
+            let intrusive = self.sweep_intrusive(thread);
+            report.freed += intrusive.freed;
+            report.retained += intrusive.retained;
+            let layouts = self.sweep_layouts(thread);
+            report.freed += layouts.freed;
+            report.retained += layouts.retained;
+
+            let mut retired = retired.borrow_mut();
+            self.adopt(thread, &mut retired);
+            if self.pinned(thread) {
+                report.retained += retired.len();
+                self.restack(thread, &mut retired);
+                continue;
+            }
+
+            let freed = self.reclaim_snapshot(&snapshot, &mut retired).freed;
+            self.undefer(thread, &mut retired);
+            let retained = retired.len();
+            self.restack(thread, &mut retired);
+            self.record(thread, ReclaimReport { freed, retained });
+            report.freed += freed;
+            report.retained += retained;
+        }
+        report
+    }
+
+    /// Pins the supplied thread until the returned guard is dropped.
+    ///
+    /// While the supplied thread is pinned, none of the pointers retired using it are reclaimed;
+    /// retiring pointers using it only adds them to its list of retired pointers. This only defers
+    /// the reclamation of the pointers retired using the supplied thread: the pointers retired
+    /// using other threads are still reclaimed, so pinning a thread does not protect the pointers
+    /// it reads (see `pin_all`). This suits operations which retire more pointers than they want
+    /// to pay to reclaim until they have finished. Pins nest and the thread remains pinned until
+    /// every guard has been dropped, at which point retired pointers are reclaimed as usual once
+    /// the threshold is next reached.
+    ///
+    /// The list of retired pointers for a pinned thread grows without bound, so pins should be held
+    /// only briefly.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn pin(&self, thread: usize) -> PinGuard<'_, T, M, I> {
+        self.pinned[thread].fetch_add(1, Relaxed);
+        PinGuard { pointers: self, thread, global: false }
+    }
+
+    /// Pins every thread until the returned guard is dropped.
+    ///
+    /// This is like `pin` except that no retired pointers are reclaimed for any thread (or from
+    /// the shared list of orphaned pointers) while the guard is held. Every thread's list of
+    /// retired pointers grows without bound while any such guard is held, so these pins should be
+    /// held even more briefly than those returned by `pin`.
+    ///
+    /// Unlike `pin`, this protects the pointers the current thread reads, but only those it loads
+    /// from a shared structure after this method returns. A reclamation which had already checked
+    /// the pins when this method was called may still deallocate a pointer which was removed from
+    /// the structure before then, so pointers loaded before the call must not be used once the
+    /// guard is the only thing protecting them.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn pin_all(&self, thread: usize) -> PinGuard<'_, T, M, I> {
+        self.pinned[thread].fetch_add(1, Relaxed);
+        self.pins.fetch_add(1, SeqCst);
+        // The sequentially consistent fence orders the pin before the loads which follow it, so a
+        // reclamation which does not observe the pin has already removed everything it frees.
+        fence(SeqCst);
+        PinGuard { pointers: self, thread, global: true }
+    }
+
+    /// Returns whether reclamation is deferred for the supplied thread.
+    ///
+    /// This issues a sequentially consistent fence first so the removal of the retired pointers
+    /// from any shared structure is ordered before the check (see `pin_all`).
+    fn pinned(&self, thread: usize) -> bool {
+        fence(SeqCst);
+        self.pinned[thread].load(Relaxed) != 0 || self.pins.load(SeqCst) != 0
+    }
+
+    /// Deallocates the non-hazardous pointers among no more than the supplied number of retired
+    /// pointers, shared fairly between the threads.
+    ///
+    /// Each thread may check up to an equal share of the budget. Successive calls resume from the
+    /// thread after the last one visited and from where the previous check of each thread's retired
+    /// pointers stopped, so one thread with many retired pointers can't use the whole budget or
+    /// starve the pointers at the end of its own list. Unlike `reclaim_all`, orphaned pointers are
+    /// not reclaimed. This gives a coordinating thread predictable reclamation latency.
+    ///
+    /// This method borrows the retired lists so it has the same restrictions as `reclaim_all`.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn reclaim_all_bounded(&self, budget: usize) -> ReclaimReport {
+        let threads = self.retired.len();
+        let mut report = ReclaimReport::default();
+        if threads == 0 || budget == 0 {
+            return report;
+        }
+
+        let snapshot = self.snapshot();
+        let share = (budget / threads).max(1);
+        let start = self.lane.load(Relaxed) % threads;
+        let mut remaining = budget;
+        let mut visited = 0;
+        while visited < threads && remaining != 0 {
+            let thread = (start + visited) % threads;
+            visited += 1;
+            if self.pinned(thread) {
+                continue;
+            }
+
+            let mut retired = self.retired[thread].borrow_mut();
+            self.adopt(thread, &mut retired);
+            let mut offset = self.offsets[thread].load(Relaxed);
+            if offset >= retired.len() {
+                offset = 0;
+            }
+            let end = retired.len().min(offset + share.min(remaining));
+            remaining -= end - offset;
+            let mut checked = retired.drain(offset..end).collect::<Vec<_>>();
+            let lane = self.reclaim_snapshot(&snapshot, &mut checked);
+            retired.splice(offset..offset, checked);
+            self.undefer(thread, &mut retired);
+            self.offsets[thread].store(offset + lane.retained, Relaxed);
+            let retained = retired.len();
+            self.restack(thread, &mut retired);
+            self.record(thread, ReclaimReport { freed: lane.freed, retained });
+            report.freed += lane.freed;
+            report.retained += lane.retained;
+        }
+        self.lane.store((start + visited) % threads, Relaxed);
+        report
+    }
+
+    /// Retires the supplied pointer using the supplied thread.
+    ///
+    /// If any thread has been registered and the supplied thread is not registered, the supplied
+    /// pointer is added to the shared list of orphaned pointers instead. The same is true of every
+    /// pointer if this collection was built with `RetiredLayout::Global`. If this collection was
+    /// built with `RetiredLayout::Stack`, the supplied pointer is pushed onto the lock-free stack
+    /// of the supplied thread instead, so any thread may retire pointers using the supplied thread.
+    ///
+    /// As a best-effort guard against retiring a pointer twice, the supplied pointer is ignored if
+    /// it is the same as the pointer most recently retired using the supplied thread. This does not
+    /// detect any other duplicates, and pointers to zero-sized values are never ignored.
+    ///
+    /// Pointers may be retired while the retired pointers of the supplied thread are being
+    /// reclaimed (e.g., by the destructor of a reclaimed value which retires its children). Such
+    /// pointers are set aside and added to the retired pointers once the reclamation finishes,
+    /// without checking the threshold, so they are reclaimed by a later reclamation.
+    ///
+    /// ```
+    /// use hazard::{BoxMemory, Memory, Pointers};
+    ///
+    /// struct Node {
+    ///     children: Vec<*mut Node>,
+    ///     pointers: *const Pointers<Node, BoxMemory>,
+    /// }
+    ///
+    /// impl Drop for Node {
+    ///     fn drop(&mut self) {
+    ///         for child in self.children.drain(..) {
+    ///             unsafe { (*self.pointers).retire(0, child); }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 1, 1);
+    /// let leaf = || BoxMemory.allocate(Node { children: vec![], pointers: &pointers });
+    /// let root = Node { children: vec![leaf(), leaf()], pointers: &pointers };
+    ///
+    /// pointers.retire(0, BoxMemory.allocate(root));
+    /// assert_eq!(pointers.pending(0), 2);
+    /// assert_eq!(pointers.reclaim(0).freed, 2);
+    /// ```
+    ///
+    /// If debug assertions are enabled, this panics if the supplied pointer is null (see
+    /// `retire_nonnull`).
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`) (blocking if this
+    /// collection was built with `RetiredLayout::Global`, lock-free if it was built with
+    /// `RetiredLayout::Stack`).
+    pub fn retire(&self, thread: usize, pointer: *mut T) {
+        debug_assert!(!pointer.is_null(), "retired a null pointer");
+        if self.shared(thread) {
+            self.push_shared(Some(pointer));
+        } else if self.layout == RetiredLayout::Stack {
+            self.retire_stack(thread, pointer);
+        } else if let Ok(mut retired) = self.retired[thread].try_borrow_mut() {
+            self.push(thread, pointer, &mut retired);
+        } else {
+            self.defer(thread, pointer);
+        }
+    }
+
+    /// Retires the supplied pointer using the supplied thread if the supplied condition returns
+    /// `true` for it and returns whether it was retired.
+    ///
+    /// The condition is called before the supplied pointer is added to the retired pointers (e.g.,
+    /// to check that the reference count of the value it points to has dropped to zero), so it may
+    /// still read the value. If it returns `false`, the supplied pointer is left untouched and the
+    /// threshold is not checked.
+    ///
+    /// **Forward progress guarantee:** that of the condition and `retire`.
+    pub fn retire_if<F>(&self, thread: usize, pointer: *mut T, condition: F) -> bool
+    where F: FnOnce(*mut T) -> bool {
+        let retire = condition(pointer);
+        if retire {
+            self.retire(thread, pointer);
+        }
+        retire
+    }
+
+    /// Retires the supplied pointer using the supplied thread like `retire`, but deallocates it
+    /// with `LayoutMemory::deallocate_layout` and the supplied layout once it has been reclaimed.
+    ///
+    /// This is for memory whose layout is not that of a single `T` (e.g., a header followed by a
+    /// dynamically sized array, or a block of an over-aligned pool). The value the pointer points
+    /// to is still dropped as a `T` before the memory is deallocated. The pointer is kept with its
+    /// layout in a separate list of retired pointers for the supplied thread which is reclaimed
+    /// along with the list used by `retire` (e.g., by `reclaim`) once the threshold is reached. The
+    /// pointers in this list are never returned by `drain` or `drain_reclaimable`.
+    ///
+    /// ```
+    /// use std::alloc::{Layout};
+    ///
+    /// use hazard::{BoxMemory, Pointers};
+    ///
+    /// // A length followed by that many elements.
+    /// let layout = Layout::new::<usize>().extend(Layout::array::<u32>(4).unwrap()).unwrap().0;
+    /// let pointer = unsafe { std::alloc::alloc(layout) } as *mut usize;
+    /// unsafe { pointer.write(4); }
+    ///
+    /// let pointers = Pointers::new(BoxMemory, 1, 1, 8);
+    /// pointers.retire_layout(0, pointer, layout);
+    /// assert_eq!(pointers.reclaim(0).freed, 1);
+    /// ```
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn retire_layout(
+        &self, thread: usize, pointer: *mut T, layout: alloc::Layout
+    ) where M: LayoutMemory {
+        debug_assert!(!pointer.is_null(), "retired a null pointer");
+        let deallocate = M::deallocate_layout as DeallocateLayout<M>;
+        let length = {
+            let mut layouts = self.layouts[thread].borrow_mut();
+            layouts.push((pointer, layout, deallocate));
+            layouts.len()
+        };
+        self.notify_retired(thread, pointer, length);
+        if length >= self.threshold() {
+            self.sweep_layouts(thread);
+        }
+    }
+
+    /// Issues a sequentially consistent fence and then retires the supplied pointer using the
+    /// supplied thread.
+    ///
+    /// Hazardous pointers are only sound if the store which unlinks a pointer from a structure is
+    /// ordered before the loads of the hazardous pointers which decide whether it can be
+    /// deallocated, just as a reader's store of a hazardous pointer must be ordered before the
+    /// load which validates it (Michael, "Hazard Pointers: Safe Memory Reclamation for Lock-Free
+    /// Objects", 2004). Acquire and release orderings do not order a store before a later load, so
+    /// without a fence a reclamation could read a stale hazardous pointer while the unlinking store
+    /// is still buffered and deallocate a pointer that a reader is about to validate successfully.
+    ///
+    /// The fence issued by this method orders every prior store of the current thread (including
+    /// the unlinking store) before any reclamation triggered by this call. `hazardous`, the
+    /// snapshots of the hazardous pointers, and the built-in reclaimers already issue such a fence
+    /// before loading the hazardous pointers, but custom reclaimers which load the hazardous
+    /// pointers themselves (e.g., with `hazardous_iter`) without a fence do not.
+    ///
+    /// **Forward progress guarantee:** that of `retire`.
+    pub fn retire_synchronized(&self, thread: usize, pointer: *mut T) {
+        fence(SeqCst);
+        self.retire(thread, pointer);
+    }
+
+    /// Retires the supplied non-null pointer using the supplied thread.
+    ///
+    /// See `retire`.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`).
+    pub fn retire_nonnull(&self, thread: usize, pointer: NonNull<T>) {
+        self.retire(thread, pointer.as_ptr());
+    }
+
+    /// Clears the hazardous pointer for the supplied domain using the supplied thread and then
+    /// retires the supplied pointer using the supplied thread.
+    ///
+    /// This prevents a thread from keeping a pointer it is retiring from being reclaimed because
+    /// it forgot to clear its own protection of it. Hazardous pointers set by other threads still
+    /// prevent the supplied pointer from being reclaimed as usual.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`).
+    pub fn retire_and_clear(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: *mut T
+    ) {
+        let (thread, domain) = (thread.into().0, domain.into().0);
+        self.clear(thread, domain);
+        self.retire(thread, pointer);
+    }
+
+    /// Replaces the value of the supplied pointer with the supplied new pointer and retires the
+    /// previous value using the supplied thread.
+    ///
+    /// This bundles the publish and reclaim steps of a read-copy-update. The swap has acquire and
+    /// release ordering so readers that load the new pointer also observe its initialization. If
+    /// a domain is supplied (either `Domain(d)` or `Some(Domain(d))`), the previous value is marked
+    /// as hazardous for that domain before it is retired so it can still be used by the caller
+    /// until that domain is cleared.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`).
+    pub fn protect_and_retire_previous(
+        &self,
+        thread: impl Into<Thread>,
+        pointer: &AtomicPtr<T>,
+        new: *mut T,
+        domain: impl Into<Option<Domain>>,
+    ) -> *mut T {
+        let thread = thread.into().0;
+        let previous = pointer.swap(new, AcqRel);
+        if let Some(domain) = domain.into() {
+            self.mark_ptr(thread, domain, previous);
+        }
+        if !previous.is_null() {
+            self.retire(thread, previous);
+        }
+        previous
+    }
+
+    /// Retires the supplied pointer using the supplied thread or returns an error if the supplied
+    /// thread is out of range or its retired pointers are already borrowed.
+    ///
+    /// See `retire` for more information.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`).
+    pub fn try_retire(&self, thread: usize, pointer: *mut T) -> Result<(), PointersError> {
+        let retired = self.retired.get(thread).ok_or(PointersError::Thread(thread))?;
+        if self.shared(thread) {
+            self.push_shared(Some(pointer));
+        } else if self.layout == RetiredLayout::Stack {
+            self.retire_stack(thread, pointer);
+        } else {
+            let mut retired = retired.try_borrow_mut()
+                .map_err(|_| PointersError::Borrowed(thread))?;
+            self.push(thread, pointer, &mut retired);
+        }
+        Ok(())
+    }
+
+    /// Retires the supplied pointer on behalf of the supplied thread.
+    ///
+    /// Unlike `retire`, this method may be called by any thread since it does not borrow the
+    /// retired pointers of the supplied thread. Instead, the supplied pointer is pushed onto a
+    /// lock-free stack which is moved into the retired pointers of the supplied thread the next
+    /// time they are reclaimed. This requires an allocation for each retired pointer.
+    ///
+    /// **Forward progress guarantee:** lock-free.
+    pub fn retire_remote(&self, thread: usize, pointer: *mut T) {
+        let node = Box::into_raw(Box::new(Remote { pointer, next: ptr::null_mut() }));
+        self.push_remote(thread, node, node, 1);
+    }
+
+    /// Pushes the supplied chain of nodes onto the lock-free stack of the supplied thread and
+    /// returns the number of pointers on the stack afterwards.
+    fn push_remote(
+        &self, thread: usize, first: *mut Remote<T>, last: *mut Remote<T>, count: usize
+    ) -> usize {
+        // The count is increased before the nodes are pushed so that it can't be decreased by a
+        // thread adopting them first.
+        let pending = self.stacked[thread].fetch_add(count, Relaxed) + count;
+        let head = &self.remote[thread];
+        let mut next = head.load(Relaxed);
+        loop {
+            unsafe { (*last).next = next; }
+            match head.compare_exchange_weak(next, first, Release, Relaxed) {
+                Ok(_) => return pending,
+                Err(actual) => next = actual,
+            }
+        }
+    }
+
+    /// Pushes the supplied retired pointers of the supplied thread onto its lock-free stack if
+    /// this collection was built with `RetiredLayout::Stack`, leaving them empty.
+    fn restack(&self, thread: usize, retired: &mut Vec<*mut T>) {
+        if self.layout != RetiredLayout::Stack || retired.is_empty() {
+            return;
+        }
+
+        let nodes = retired.drain(..).rev().map(|pointer| {
+            Box::into_raw(Box::new(Remote { pointer, next: ptr::null_mut() }))
+        }).collect::<Vec<_>>();
+        self.link_remote(thread, nodes);
+    }
+
+    /// Links the supplied nodes (newest first) and pushes them onto the lock-free stack of the
+    /// supplied thread.
+    fn link_remote(&self, thread: usize, nodes: Vec<*mut Remote<T>>) {
+        if let (Some(&first), Some(&last)) = (nodes.first(), nodes.last()) {
+            for pair in nodes.windows(2) {
+                unsafe { (*pair[0]).next = pair[1]; }
+            }
+            self.push_remote(thread, first, last, nodes.len());
+        }
+    }
+
+    /// Retires the supplied pointer using the supplied thread by pushing it onto its lock-free
+    /// stack and reclaims the stack if the threshold has been reached.
+    fn retire_stack(&self, thread: usize, pointer: *mut T) {
+        let node = Box::into_raw(Box::new(Remote { pointer, next: ptr::null_mut() }));
+        let pending = self.push_remote(thread, node, node, 1);
+        self.tally(thread, pending, 1, 0);
+        self.notify_retired(thread, pointer, pending);
+        if pending >= self.threshold() {
+            self.sweep_stack(thread);
+        }
+    }
+
+    /// Reclaims the lock-free stack of retired pointers of the supplied thread with the reclaimer
+    /// and pushes the pointers that are still hazardous back onto it.
+    ///
+    /// Since the stack is popped all at once, any number of threads may do this concurrently.
+    /// Nothing is reclaimed if the supplied thread or every thread is pinned.
+    fn sweep_stack(&self, thread: usize) -> ReclaimReport {
+        if self.pinned(thread) {
+            return ReclaimReport { freed: 0, retained: self.stacked[thread].load(Relaxed) };
+        }
+
+        #[cfg(feature="timing")]
+        let start = Instant::now();
+        let mut node = self.remote[thread].swap(ptr::null_mut(), Acquire);
+        let (mut nodes, mut retired) = (vec![], vec![]);
+        while !node.is_null() {
+            nodes.push(node);
+            retired.push(unsafe { (*node).pointer });
+            node = unsafe { (*node).next };
+        }
+        self.stacked[thread].fetch_sub(nodes.len(), Relaxed);
+
+        // The stack is newest first but the reclaimer expects the oldest pointers first. The
+        // sweeping thread is not the owner of the stack, so no domains are skipped.
+        retired.reverse();
+        let before = retired.len();
+        let previous = SWEEPING.with(|s| s.replace((self as *const Self as usize, usize::MAX)));
+        self.reclaim_ordered(&mut retired);
+        SWEEPING.with(|s| s.set(previous));
+        let report = ReclaimReport { freed: before - retired.len(), retained: retired.len() };
+
+        for node in nodes.drain(retired.len()..) {
+            unsafe { drop(Box::from_raw(node)); }
+        }
+        for (node, pointer) in nodes.iter().zip(retired.into_iter().rev()) {
+            unsafe { (**node).pointer = pointer; }
+        }
+        self.link_remote(thread, nodes);
+        self.record(thread, report);
+        #[cfg(feature="timing")]
+        if let Some(ref observer) = self.observer {
+            observer.reclaimed_in(thread, start.elapsed(), report);
+        }
+        report
+    }
+
+    /// Adds the supplied pointer to the deferred retired pointers of the supplied thread, which are
+    /// added to its retired pointers once they are no longer borrowed.
+    fn defer(&self, thread: usize, pointer: *mut T) {
+        let mut deferred = self.deferred[thread].take();
+        deferred.push(pointer);
+        self.deferred[thread].set(deferred);
+    }
+
+    /// Returns the number of deferred retired pointers of the supplied thread.
+    fn deferred_len(&self, thread: usize) -> usize {
+        let deferred = self.deferred[thread].take();
+        let length = deferred.len();
+        self.deferred[thread].set(deferred);
+        length
+    }
+
+    /// Moves the deferred retired pointers of the supplied thread into the supplied retired
+    /// pointers.
+    fn undefer(&self, thread: usize, retired: &mut Vec<*mut T>) {
+        let deferred = self.deferred[thread].take();
+        if !deferred.is_empty() {
+            retired.extend_from_slice(&deferred);
+            self.tally(thread, retired.len(), deferred.len(), 0);
+        }
+    }
+
+    /// Returns whether pointers retired using the supplied thread are added to the shared list
+    /// of orphaned pointers.
+    fn shared(&self, thread: usize) -> bool {
+        self.layout == RetiredLayout::Global ||
+            (self.dynamic.load(Relaxed) && !self.registered(thread))
+    }
+
+    /// Adds the supplied pointers to the shared list of orphaned pointers and reclaims it if the
+    /// threshold has been reached.
+    ///
+    /// Pointers added while this thread is reclaiming pointers (i.e., by destructors) are left for
+    /// the next reclamation.
+    fn push_shared<P>(&self, pointers: P) where P: IntoIterator<Item=*mut T> {
+        let mut orphans = self.orphans();
+        orphans.extend(pointers);
+        if orphans.len() >= self.threshold() && self.sweeping().is_none() {
+            drop(orphans);
+            let snapshot = self.snapshot();
+            self.sweep_shared(&snapshot);
+        }
+    }
+
+    /// Deallocates the orphaned pointers that are not hazardous according to the supplied
+    /// snapshot.
+    ///
+    /// The orphaned pointers are taken out of the lock while they are checked and deallocated so
+    /// that destructors may retire pointers, and the survivors are put back in front of any
+    /// pointers orphaned in the meantime.
+    fn sweep_shared(&self, snapshot: &[usize]) -> ReclaimReport {
+        let mut orphans = mem::take(&mut *self.orphans());
+        if orphans.is_empty() {
+            return ReclaimReport::default();
+        }
+
+        let previous = SWEEPING.with(|s| s.replace((self as *const Self as usize, usize::MAX)));
+        let report = self.reclaim_snapshot(snapshot, &mut orphans);
+        SWEEPING.with(|s| s.set(previous));
+        let mut shared = self.orphans();
+        orphans.append(&mut shared);
+        *shared = orphans;
+        report
+    }
+
+    /// Deallocates the pointers in the intrusive list of retired pointers for the supplied thread
+    /// that are not hazardous using the retire link accessor recorded for the list.
+    fn sweep_intrusive(&self, thread: usize) -> ReclaimReport {
+        let (mut pointer, length, link) = self.intrusive[thread].get();
+        let link = match link {
+            Some(link) if !pointer.is_null() => link,
+            _ => return ReclaimReport::default(),
+        };
+        if self.pinned(thread) {
+            return ReclaimReport { freed: 0, retained: length };
+        }
+
+        self.intrusive[thread].set((ptr::null_mut(), 0, Some(link)));
+        let (mut head, mut tail) = (ptr::null_mut(), ptr::null_mut());
+        let mut report = ReclaimReport::default();
+        let previous = SWEEPING.with(|s| s.replace((self as *const Self as usize, usize::MAX)));
+        while !pointer.is_null() {
+            let current = pointer;
+            pointer = unsafe { (*link(current)).load(Relaxed) };
+            if self.hazardous(current) {
+                unsafe { (*link(current)).store(head, Relaxed); }
+                if head.is_null() {
+                    tail = current;
+                }
+                head = current;
+                report.retained += 1;
+            } else {
+                unsafe { self.free(current); }
+                report.freed += 1;
+            }
+        }
+        SWEEPING.with(|s| s.set(previous));
+
+        let (pushed, length, _) = self.intrusive[thread].get();
+        if head.is_null() {
+            head = pushed;
+        } else {
+            unsafe { (*link(tail)).store(pushed, Relaxed); }
+        }
+        self.intrusive[thread].set((head, report.retained + length, Some(link)));
+        self.notify_reclaimed(thread, report);
+        report
+    }
+
+    /// Deallocates the pointers retired with `retire_layout` using the supplied thread that are
+    /// not hazardous.
+    ///
+    /// Pointers retired with `retire_layout` while they are being reclaimed (e.g., by the
+    /// destructor of a reclaimed value) are kept for the next reclamation.
+    fn sweep_layouts(&self, thread: usize) -> ReclaimReport {
+        let mut layouts = mem::take(&mut *self.layouts[thread].borrow_mut());
+        if layouts.is_empty() || self.pinned(thread) {
+            let report = ReclaimReport { freed: 0, retained: layouts.len() };
+            self.relayout(thread, layouts);
+            return report;
+        }
+
+        let before = layouts.len();
+        let previous = SWEEPING.with(|s| s.replace((self as *const Self as usize, thread)));
+        layouts.retain(|layouted| {
+            let hazardous = self.hazardous(layouted.0);
+            if !hazardous {
+                unsafe { self.free_layout(*layouted); }
+            }
+            hazardous
+        });
+        SWEEPING.with(|s| s.set(previous));
+        let report = ReclaimReport { freed: before - layouts.len(), retained: layouts.len() };
+        self.relayout(thread, layouts);
+        report
+    }
+
+    /// Adds the supplied pointers retired with `retire_layout` back to the pointers retired with
+    /// `retire_layout` using the supplied thread, before those retired in the meantime.
+    fn relayout(&self, thread: usize, mut layouts: Vec<Layouted<T, M>>) {
+        let mut retired = self.layouts[thread].borrow_mut();
+        layouts.append(&mut retired);
+        *retired = layouts;
+    }
+
+    /// Moves the pointers retired on behalf of the supplied thread into the supplied retired
+    /// pointers.
+    fn adopt(&self, thread: usize, retired: &mut Vec<*mut T>) {
+        let mut node = self.remote[thread].swap(ptr::null_mut(), Acquire);
+        if node.is_null() {
+            return;
+        }
+
+        let mut adopted = vec![];
+        while !node.is_null() {
+            let remote = unsafe { Box::from_raw(node) };
+            adopted.push(remote.pointer);
+            node = remote.next;
+        }
+        self.stacked[thread].fetch_sub(adopted.len(), Relaxed);
+        if self.skip_own && self.layout != RetiredLayout::Stack {
+            // These pointers were not necessarily retired by the user of the supplied thread, so
+            // they must be checked against its hazardous pointers too.
+            self.push_shared(adopted);
+        } else {
+            retired.extend_from_slice(&adopted);
+            self.tally(thread, retired.len(), adopted.len(), 0);
+        }
+    }
+
+    /// Adds the supplied pointer to the supplied retired pointers of the supplied thread.
+    fn push(&self, thread: usize, pointer: *mut T, retired: &mut Vec<*mut T>) {
+        // Every pointer to a zero-sized value may be the same, so such pointers are never ignored.
+        if mem::size_of::<T>() != 0 && retired.last() == Some(&pointer) {
+            return;
+        }
+
+        retired.push(pointer);
+        self.tally(thread, retired.len(), 1, 0);
+        self.notify_retired(thread, pointer, retired.len());
+        if self.elapsed(thread, 1) || retired.len() >= self.threshold() {
+            self.sweep(thread, retired);
+        }
+    }
+
+    /// Counts the supplied number of pointers as retired using the supplied thread and returns
+    /// whether the scan interval has elapsed.
+    fn elapsed(&self, thread: usize, retires: usize) -> bool {
+        let before = self.retires[thread].get();
+        let after = before.wrapping_add(retires);
+        self.retires[thread].set(after);
+        let interval = self.interval.load(Relaxed);
+        interval != 0 && after / interval != before / interval
+    }
+}
+
+impl<T, M, I> Pointers<T, M, I> where T: Retirable, M: Memory, I: PtrIdentity<T> {
+    //- Accessors --------------------------------
+
+    /// Retires the supplied pointer using the supplied thread by adding it to an intrusive list of
+    /// retired pointers threaded through the retire links of the retired values.
+    ///
+    /// This avoids growing a list of retired pointers for each retired pointer. The intrusive list
+    /// is separate from the list used by `retire`, it is always reclaimed by checking each pointer
+    /// against every hazardous pointer, and it is not affected by thread registration.
+    ///
+    /// # Safety
+    ///
+    /// The supplied pointer must point to a valid value which has not already been retired.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`).
+    pub unsafe fn retire_intrusive(&self, thread: usize, pointer: *mut T) {
+        let (head, length, _) = self.intrusive[thread].get();
+        (*pointer).retire_link().store(head, Relaxed);
+        self.intrusive[thread].set((pointer, length + 1, Some(link::<T>)));
+        self.notify_retired(thread, pointer, length + 1);
+        if length + 1 >= self.threshold() {
+            self.reclaim_intrusive(thread);
+        }
+    }
+
+    /// Deallocates the pointers in the intrusive list of retired pointers for the supplied thread
+    /// that are not hazardous.
+    ///
+    /// Pointers retired with `retire_intrusive` while the list is being reclaimed (e.g., by the
+    /// destructor of a reclaimed value which retires its children) are kept for the next
+    /// reclamation.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains * retired`).
+    pub fn reclaim_intrusive(&self, thread: usize) -> ReclaimReport {
+        self.sweep_intrusive(thread)
+    }
+}
+
+impl<T, M, I> HazardView for Pointers<T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn is_address_hazardous(&self, address: *const ()) -> bool {
+        self.hazardous(address as *mut T)
+    }
+}
+
+impl<T, I> Pointers<T, BoxMemory, I> where I: PtrIdentity<T> {
+    //- Accessors --------------------------------
+
+    /// Retires the supplied box using the supplied thread.
+    ///
+    /// Unlike `retire`, this method guarantees that the retired pointer refers to a heap allocation
+    /// that is owned by this collection and may be deallocated by it.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`).
+    pub fn retire_box(&self, thread: usize, boxed: Box<T>) {
+        self.retire(thread, Box::into_raw(boxed));
+    }
+
+    /// Retires the supplied boxes using the supplied thread.
+    ///
+    /// This is like calling `retire_box` for each box, except that the retired pointers for the
+    /// supplied thread are borrowed once and the threshold is only checked after every box has
+    /// been retired.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`boxes + threads * threads`).
+    pub fn retire_boxes<B>(&self, thread: usize, boxes: B) where B: IntoIterator<Item=Box<T>> {
+        let boxes = boxes.into_iter().map(Box::into_raw);
+        if self.shared(thread) {
+            self.push_shared(boxes);
+            return;
+        } else if self.layout == RetiredLayout::Stack {
+            return boxes.for_each(|p| self.retire_stack(thread, p));
+        }
+
+        let mut retired = match self.retired[thread].try_borrow_mut() {
+            Ok(retired) => retired,
+            Err(_) => return boxes.for_each(|p| self.defer(thread, p)),
+        };
+        let before = retired.len();
+        for pointer in boxes {
+            retired.push(pointer);
+            self.notify_retired(thread, pointer, retired.len());
+        }
+        let retires = retired.len() - before;
+        self.tally(thread, retired.len(), retires, 0);
+        if self.elapsed(thread, retires) || retired.len() >= self.threshold() {
+            self.sweep(thread, &mut retired);
+        }
+    }
+}
+
+impl<T, M, I> Drop for Pointers<T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn drop(&mut self) {
+        if self.forget.load(Relaxed) {
+            return;
+        }
+
+        if cfg!(debug_assertions) && !thread::panicking() {
+            if let Some(pointer) = self.hazardous_iter().next() {
+                panic!(
+                    "dropped Pointers while a hazard is still active ({:?}), a reader thread may \
+                     be live",
+                    pointer,
+                );
+            }
+        }
+
+        for (thread, retired) in self.retired.iter().enumerate() {
+            self.adopt(thread, &mut retired.borrow_mut());
+        }
+        for intrusive in &*self.intrusive {
+            while let (mut pointer, _, Some(link)) = intrusive.replace((ptr::null_mut(), 0, None)) {
+                if pointer.is_null() {
+                    break;
+                }
+
+                while !pointer.is_null() {
+                    let current = pointer;
+                    pointer = unsafe { (*link(current)).load(Relaxed) };
+                    unsafe { self.free(current); }
+                }
+            }
+        }
+        for retired in &*self.retired {
+            for pointer in &*retired.borrow() {
+                unsafe { self.free(*pointer); }
+            }
+        }
+        for layouts in &*self.layouts {
+            loop {
+                let layouted = layouts.borrow_mut().pop();
+                match layouted {
+                    Some(layouted) => unsafe { self.free_layout(layouted); },
+                    None => break,
+                }
+            }
+        }
+        loop {
+            let mut pointers = mem::take(&mut *self.orphans());
+            for deferred in &*self.deferred {
+                pointers.extend(deferred.take());
+            }
+            if pointers.is_empty() {
+                break;
+            }
+
+            for pointer in pointers {
+                unsafe { self.free(pointer); }
+            }
+        }
+    }
+}
+
+impl<T, M, I> fmt::Debug for Pointers<T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Pointers").field("hazardous", &self.hazardous).finish()
+    }
+}
+
+// PointersBuilder _______________________________
+
+/// A builder for `Pointers`.
+pub struct PointersBuilder<T, M, I=Address> where M: Memory, I: PtrIdentity<T> {
+    memory: M,
+    threads: usize,
+    domains: usize,
+    threshold: usize,
+    reclaimer: Box<dyn Reclaimer<T, M, I>>,
+    reclaim_on_clear: usize,
+    retire_only: usize,
+    order: FreeOrder,
+    skip_own: bool,
+    policy: ThresholdPolicy,
+    layout: RetiredLayout,
+}
+
+impl<T, M> PointersBuilder<T, M> where M: Memory {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `PointersBuilder`.
+    ///
+    /// See `Pointers::new` for the meaning of the parameters.
+    pub fn new(memory: M, threads: usize, domains: usize, threshold: usize) -> Self {
+        Self::with_identity(memory, threads, domains, threshold)
+    }
+}
+
+impl<T, M, I> PointersBuilder<T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `PointersBuilder` for a `Pointers` which uses the supplied pointer identity
+    /// to determine whether retired pointers are hazardous.
+    ///
+    /// See `Pointers::new` for the meaning of the parameters.
+    pub fn with_identity(memory: M, threads: usize, domains: usize, threshold: usize) -> Self {
+        PointersBuilder {
+            memory,
+            threads,
+            domains,
+            threshold,
+            reclaimer: Box::new(NaiveScan),
+            reclaim_on_clear: 0,
+            retire_only: 0,
+            order: FreeOrder::Fifo,
+            skip_own: false,
+            policy: ThresholdPolicy::Fixed,
+            layout: RetiredLayout::PerThread,
+        }
+    }
 
-#![warn(missing_copy_implementations, missing_debug_implementations, missing_docs)]
+    //- Mutators ---------------------------------
 
-use std::fmt;
-use std::ops;
-use std::ptr;
-use std::cell::{RefCell};
-use std::sync::atomic::{AtomicPtr};
-use std::sync::atomic::Ordering::*;
+    /// Sets the maximum number of retired pointers that are checked and possibly reclaimed by
+    /// `Pointers::clear` (defaults to `0` which disables reclamation in `Pointers::clear`).
+    ///
+    /// This piggybacks reclamation onto the end of critical sections at the cost of increasing
+    /// the latency of `Pointers::clear`. The oldest retired pointers of the thread are reclaimed
+    /// with the reclaimer (like any other reclamation, this is recorded and reported to the
+    /// observer).
+    pub fn reclaim_on_clear(mut self, limit: usize) -> Self {
+        self.reclaim_on_clear = limit;
+        self
+    }
 
-//================================================
-// Traits
-//================================================
+    /// Sets the reclaimer which reclaims retired pointers (defaults to `NaiveScan`).
+    ///
+    /// The built-in reclaimers are `NaiveScan`, `BatchScan`, `SnapshotSet`, `Sorted`, `Bounded`,
+    /// and `DecisionScan`. Each of them deallocates exactly the retired pointers which are not
+    /// hazardous (or, for `Bounded`, those among the oldest retired pointers).
+    ///
+    /// ```
+    /// use hazard::*;
+    ///
+    /// fn check<R>(reclaimer: R) where R: Reclaimer<usize, BoxMemory> + 'static {
+    ///     let pointers = PointersBuilder::new(BoxMemory, 2, 1, usize::MAX)
+    ///         .reclaimer(reclaimer)
+    ///         .build();
+    ///     let retired = (0..8).map(|v| BoxMemory.allocate(v)).collect::<Vec<_>>();
+    ///     for (domain, pointer) in retired.iter().step_by(4).enumerate() {
+    ///         pointers.mark_ptr(domain, 0, *pointer);
+    ///     }
+    ///     for pointer in &retired {
+    ///         pointers.retire(0, *pointer);
+    ///     }
+    ///
+    ///     assert_eq!(pointers.reclaim(0), ReclaimReport { freed: 6, retained: 2 });
+    ///     assert_eq!(pointers.drain(0), [retired[0], retired[4]]);
+    ///     pointers.clear(0, 0);
+    ///     pointers.clear(1, 0);
+    ///     pointers.retire(0, retired[0]);
+    ///     pointers.retire(0, retired[4]);
+    /// }
+    ///
+    /// check(NaiveScan);
+    /// check(BatchScan);
+    /// check(SnapshotSet);
+    /// check(Sorted);
+    /// check(Bounded { max_checks: 8 });
+    /// check(DecisionScan::new(|_: *mut usize| ReclaimDecision::Free));
+    /// ```
+    pub fn reclaimer<R>(mut self, reclaimer: R) -> Self where R: Reclaimer<T, M, I> + 'static {
+        self.reclaimer = Box::new(reclaimer);
+        self
+    }
 
-// Memory ________________________________________
+    /// Sets the number of threads which only retire pointers (defaults to `0`).
+    ///
+    /// The last `threads` threads have no hazardous pointers, so they can't mark pointers as
+    /// hazardous but they don't add to the cost of scanning the hazardous pointers or to the memory
+    /// used by this collection. This suits producer threads which retire pointers without reading
+    /// them. The other threads still have `domains` hazardous pointers each. Retire-only threads
+    /// are registered with `Pointers::register_retire_only` rather than `Pointers::register`.
+    pub fn retire_only(mut self, threads: usize) -> Self {
+        self.retire_only = threads;
+        self
+    }
 
-/// A type that can allocate and deallocate memory.
-pub trait Memory {
-    /// Allocates memory.
-    fn allocate<T>(&self, value: T) -> *mut T;
-    /// Deallocates the memory associated with the supplied pointer.
-    unsafe fn deallocate<T>(&self, pointer: *mut T);
+    /// Sets the order in which the retired pointers of a list are deallocated when it is reclaimed
+    /// (defaults to `FreeOrder::Fifo`).
+    ///
+    /// This applies to the reclaimer (including the bounded reclamations of `reclaim_on_clear` and
+    /// `Pointers::reclaim_with_budget`, which always check the oldest retired pointers or resume
+    /// where they stopped but deallocate them in this order) and to `Pointers::reclaim_all` and
+    /// `Pointers::reclaim_orphans`, but not to intrusive lists of retired pointers (which are
+    /// always newest first).
+    pub fn free_order(mut self, order: FreeOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets whether the reclaimer ignores the hazardous pointers of the thread whose retired
+    /// pointers it is reclaiming (defaults to `false`).
+    ///
+    /// This reduces the number of hazardous pointers checked for each retired pointer by
+    /// `domains` (i.e., by a factor of `1 / threads`), which is most significant for small numbers
+    /// of threads.
+    ///
+    /// **This is only sound if no pointer retired using a thread is still protected by the
+    /// hazardous pointers of that thread** (e.g., if every pointer is retired with
+    /// `Pointers::retire_and_clear` or after clearing its hazardous pointer). The condition is per
+    /// thread index rather than per OS thread: whoever uses a thread index to retire a pointer must
+    /// not be protecting it with that thread index at that time or afterwards. Otherwise, a
+    /// reclamation could deallocate a pointer that the thread is still using.
+    ///
+    /// Pointers retired on behalf of a thread with `Pointers::retire_remote` were not retired by
+    /// the user of that thread, so they are moved to the shared list of orphaned pointers (which is
+    /// reclaimed without skipping any hazardous pointers) instead of the retired pointers of the
+    /// thread. This option is ignored with `RetiredLayout::Stack`, since any thread may retire
+    /// pointers using any thread.
+    pub fn skip_own_domains(mut self, skip: bool) -> Self {
+        self.skip_own = skip;
+        self
+    }
+
+    /// Sets how the threshold at which a list of retired pointers is reclaimed is determined
+    /// (defaults to `ThresholdPolicy::Fixed`).
+    ///
+    /// `ThresholdPolicy::Scaled` keeps the number of retired pointers proportional to the number
+    /// of threads which are actually using the collection. The policy is evaluated each time a
+    /// pointer is retired, so it takes effect as soon as a thread registers or unregisters.
+    ///
+    /// ```
+    /// use hazard::{BoxMemory, Memory, PointersBuilder, ThresholdPolicy};
+    ///
+    /// let pointers = PointersBuilder::new(BoxMemory, 4, 1, usize::MAX)
+    ///     .threshold_policy(ThresholdPolicy::Scaled { per_thread: 2 })
+    ///     .build();
+    /// let thread = pointers.register().unwrap();
+    /// pointers.retire(thread, BoxMemory.allocate(17));
+    /// pointers.retire(thread, BoxMemory.allocate(322));
+    /// assert_eq!(pointers.pending(thread), 0);
+    /// ```
+    pub fn threshold_policy(mut self, policy: ThresholdPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets where retired pointers are stored (defaults to `RetiredLayout::PerThread`).
+    ///
+    /// With `RetiredLayout::Global`, every retired pointer is added to the shared list of orphaned
+    /// pointers, which is reclaimed (as if by `Pointers::reclaim_orphans`, so without the
+    /// reclaimer) once it reaches the threshold (as it is with any layout) and whenever any thread
+    /// is reclaimed with `Pointers::reclaim`. Retiring a pointer is then blocking rather than
+    /// wait-free. With `RetiredLayout::Stack`, retiring a pointer is lock-free.
+    pub fn retired_layout(mut self, layout: RetiredLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    //- Accessors --------------------------------
+
+    /// Constructs a new `Pointers` with the options set on this builder.
+    pub fn build(self) -> Pointers<T, M, I> {
+        Pointers::from_builder(self)
+    }
+
+    /// Constructs a new `Pointers` with the options set on this builder or returns an error if
+    /// allocation fails.
+    pub fn try_build(self) -> Result<Pointers<T, M, I>, TryReserveError> {
+        Pointers::try_from_builder(self)
+    }
 }
 
-//================================================
-// Structs
-//================================================
+impl<T, M, I> fmt::Debug for PointersBuilder<T, M, I> where M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("PointersBuilder")
+            .field("threads", &self.threads)
+            .field("domains", &self.domains)
+            .field("threshold", &self.threshold)
+            .field("reclaim_on_clear", &self.reclaim_on_clear)
+            .field("retire_only", &self.retire_only)
+            .field("order", &self.order)
+            .field("skip_own", &self.skip_own)
+            .field("policy", &self.policy)
+            .field("layout", &self.layout)
+            .finish()
+    }
+}
 
-// AlignVec ______________________________________
+// PointersConfig ________________________________
 
-#[cfg(target_pointer_width="32")]
-const POINTERS: usize = 32;
-#[cfg(target_pointer_width="64")]
-const POINTERS: usize = 16;
+/// The dimensions and threshold of a `Pointers`.
+///
+/// See `Pointers::new` for the meaning of the fields.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PointersConfig {
+    /// The maximum number of threads.
+    pub threads: usize,
+    /// The maximum number of hazardous pointers per thread.
+    pub domains: usize,
+    /// The maximum size lists of retired pointers can grow to.
+    pub threshold: usize,
+}
 
-/// A `Vec` aligned to the size of a cacheline.
-#[repr(C)]
-pub struct AlignVec<T> {
-    vec: Vec<T>,
-    _padding: [usize; POINTERS - 3],
+// ReclaimBudget _________________________________
+
+/// A limit on the number of retired pointers checked by `Pointers::reclaim_with_budget`.
+///
+/// A budget is decremented by each call it is passed to, so a single budget can be shared between
+/// several calls (e.g., for several threads) and refilled periodically (e.g., on every tick of a
+/// cooperative scheduler).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReclaimBudget {
+    /// The number of retired pointers which may still be checked.
+    pub remaining: usize,
 }
 
-impl<T> AlignVec<T> {
+impl ReclaimBudget {
     //- Constructors -----------------------------
 
-    /// Constructs a new `AlignVec`.
-    pub fn new(vec: Vec<T>) -> Self {
-        AlignVec { vec: vec, _padding: [0; POINTERS - 3] }
+    /// Constructs a new `ReclaimBudget` with the supplied number of retired pointers.
+    pub fn new(remaining: usize) -> Self {
+        ReclaimBudget { remaining }
     }
-}
 
-impl<T> fmt::Debug for AlignVec<T> where T: fmt::Debug {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "{:?}", &self.vec)
+    //- Accessors --------------------------------
+
+    /// Returns whether no more retired pointers may be checked.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0
     }
 }
 
-impl<T> ops::Deref for AlignVec<T> {
-    type Target = Vec<T>;
+// ReclaimReport _________________________________
 
-    fn deref(&self) -> &Self::Target {
-        &self.vec
+/// The outcome of reclaiming one or more lists of retired pointers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReclaimReport {
+    /// The number of retired pointers that were deallocated.
+    pub freed: usize,
+    /// The number of retired pointers that were still hazardous and were kept.
+    pub retained: usize,
+}
+
+impl fmt::Display for ReclaimReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "freed {}, retained {}", self.freed, self.retained)
     }
 }
 
-impl<T> ops::DerefMut for AlignVec<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.vec
+impl From<ReclaimReport> for (usize, usize) {
+    fn from(report: ReclaimReport) -> (usize, usize) {
+        (report.freed, report.retained)
     }
 }
 
-// BoxMemory _____________________________________
+// SlabMemory ____________________________________
 
-/// An allocator that uses `Box` to allocate and deallocate memory.
-#[derive(Copy, Clone, Debug)]
-pub struct BoxMemory;
+/// An allocator that allocates values from a fixed number of preallocated blocks.
+///
+/// Every value is stored in a block of type `B`, so each value must be no larger than `B` and
+/// must not require a greater alignment than `B`. Allocating never uses the global allocator;
+/// `allocate` panics and `try_allocate` fails for a value which doesn't fit in a block or once
+/// every block is in use, and deallocating a value makes its block available again. Values which
+/// are still allocated when the `SlabMemory` is dropped are leaked (their destructors are not run).
+///
+/// ```
+/// use hazard::{Memory, SlabMemory};
+///
+/// let slab = SlabMemory::<[u64; 4]>::new(2);
+/// let a = slab.allocate(17u64);
+/// let b = slab.allocate([322u32; 8]);
+/// assert_eq!(slab.try_allocate(42u8), Err(42));
+/// assert_eq!(slab.try_allocate([42u64; 5]), Err([42; 5]));
+///
+/// unsafe { slab.deallocate(a); }
+/// let c = slab.try_allocate(42u8).unwrap();
+/// assert_eq!(slab.available(), 0);
+///
+/// unsafe { slab.deallocate(b); }
+/// unsafe { slab.deallocate(c); }
+/// assert_eq!(slab.available(), 2);
+/// ```
+pub struct SlabMemory<B> {
+    blocks: Box<[UnsafeCell<MaybeUninit<B>>]>,
+    free: Mutex<Vec<usize>>,
+}
 
-impl Memory for BoxMemory {
+unsafe impl<B> Send for SlabMemory<B> where B: Send { }
+unsafe impl<B> Sync for SlabMemory<B> where B: Send { }
+
+impl<B> SlabMemory<B> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `SlabMemory` with the supplied number of blocks.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `B` is zero-sized.
+    pub fn new(blocks: usize) -> Self {
+        assert!(mem::size_of::<B>() != 0, "SlabMemory blocks must not be zero-sized");
+        SlabMemory {
+            blocks: (0..blocks).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+            free: Mutex::new((0..blocks).rev().collect()),
+        }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the number of blocks in this slab.
+    pub fn capacity(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns the number of blocks in this slab which are not in use.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn available(&self) -> usize {
+        self.free().len()
+    }
+
+    fn free(&self) -> MutexGuard<'_, Vec<usize>> {
+        self.free.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns whether a value of type `T` fits in a block.
+    fn fits<T>(&self) -> bool {
+        mem::size_of::<T>() <= mem::size_of::<B>() && mem::align_of::<T>() <= mem::align_of::<B>()
+    }
+
+    /// Returns the index of the block the supplied pointer points to.
+    fn index<T>(&self, pointer: *mut T) -> usize {
+        let offset = (pointer as usize).wrapping_sub(self.blocks.as_ptr() as usize);
+        let index = offset / mem::size_of::<B>();
+        assert!(
+            index < self.blocks.len() && offset % mem::size_of::<B>() == 0,
+            "deallocated {:?} which was not allocated by this SlabMemory",
+            pointer,
+        );
+        index
+    }
+}
+
+impl<B> Memory for SlabMemory<B> {
+    /// # Panics
+    ///
+    /// This panics if `T` is larger than `B`, if `T` requires a greater alignment than `B`, or if
+    /// every block is in use.
     fn allocate<T>(&self, value: T) -> *mut T {
-        Box::into_raw(Box::new(value))
+        assert!(
+            self.fits::<T>(),
+            "{} does not fit in a SlabMemory block of {}",
+            any::type_name::<T>(),
+            any::type_name::<B>(),
+        );
+        match self.try_allocate(value) {
+            Ok(pointer) => pointer,
+            Err(_) => panic!("SlabMemory is full ({} blocks)", self.blocks.len()),
+        }
+    }
+
+    /// Fails if `T` is larger than `B`, if `T` requires a greater alignment than `B`, or if every
+    /// block is in use.
+    fn try_allocate<T>(&self, value: T) -> Result<*mut T, T> {
+        if !self.fits::<T>() {
+            return Err(value);
+        }
+        match self.free().pop() {
+            Some(index) => {
+                let pointer = self.blocks[index].get() as *mut T;
+                unsafe { pointer.write(value); }
+                Ok(pointer)
+            },
+            None => Err(value),
+        }
     }
 
     unsafe fn deallocate<T>(&self, pointer: *mut T) {
-        assert!(!pointer.is_null());
-        Box::from_raw(pointer);
+        let index = self.index(pointer);
+        ptr::drop_in_place(pointer);
+        self.free().push(index);
+    }
+
+    unsafe fn deallocate_batch<T>(&self, pointers: &[*mut T]) {
+        let indices = pointers.iter().map(|p| self.index(*p)).collect::<Vec<_>>();
+        for pointer in pointers {
+            ptr::drop_in_place(*pointer);
+        }
+        self.free().extend(indices);
     }
 }
 
-// Pointers ______________________________________
+impl<B> LayoutMemory for SlabMemory<B> {
+    /// # Panics
+    ///
+    /// This panics if the supplied layout is larger than `B` or requires a greater alignment than
+    /// `B`.
+    unsafe fn deallocate_layout(&self, pointer: *mut u8, layout: alloc::Layout) {
+        let fits = layout.size() <= mem::size_of::<B>() && layout.align() <= mem::align_of::<B>();
+        assert!(
+            fits,
+            "{:?} does not fit in a SlabMemory block of {}",
+            layout,
+            any::type_name::<B>(),
+        );
+        let index = self.index(pointer);
+        self.free().push(index);
+    }
+}
 
-/// A collection of hazardous pointers.
-#[repr(C)]
-pub struct Pointers<T, M> where M: Memory {
-    hazardous: AlignVec<Vec<AtomicPtr<T>>>,
-    retired: AlignVec<RefCell<Vec<*mut T>>>,
-    threshold: usize,
+impl<B> fmt::Debug for SlabMemory<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("SlabMemory")
+            .field("capacity", &self.capacity())
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+// Slot __________________________________________
+
+/// A token for a domain of a thread of a `Pointers` which has been marked.
+///
+/// See `Pointers::mark_slot`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Slot {
+    thread: usize,
+    domain: usize,
+}
+
+impl Slot {
+    //- Accessors --------------------------------
+
+    /// Returns the thread of this slot.
+    pub fn thread(&self) -> usize {
+        self.thread
+    }
+
+    /// Returns the domain of this slot.
+    pub fn domain(&self) -> usize {
+        self.domain
+    }
+}
+
+// SnapshotSet ___________________________________
+
+/// A reclaimer that loads every hazardous pointer once into a hash set and checks each retired
+/// pointer against the set.
+///
+/// This is cheaper than `NaiveScan` when there are many retired pointers and many hazardous
+/// pointers, at the cost of allocating the set for each reclamation. Pointer identities which
+/// protect more than one address (e.g., `Interior`) fall back to checking every address in the
+/// set.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SnapshotSet;
+
+impl<T, M, I> Reclaimer<T, M, I> for SnapshotSet where M: Memory, I: PtrIdentity<T> {
+    fn reclaim(&self, pointers: &Pointers<T, M, I>, retired: &mut Vec<*mut T>) {
+        fence(SeqCst);
+        let snapshot = pointers.hazardous_iter().map(I::address).collect::<HashSet<_>>();
+        retired.retain(|p| {
+            let range = I::range(*p);
+            let hazardous = if range.len() == 1 {
+                snapshot.contains(&range.start)
+            } else {
+                snapshot.iter().any(|a| range.contains(a))
+            };
+            if !hazardous {
+                unsafe { pointers.free(*p); }
+            }
+            hazardous
+        });
+    }
+}
+
+// Sorted ________________________________________
+
+/// A reclaimer that loads every hazardous pointer once into a sorted list and checks each retired
+/// pointer against the list with a binary search.
+///
+/// Like `SnapshotSet`, this is cheaper than `NaiveScan` when there are many retired pointers and
+/// many hazardous pointers, and it handles pointer identities which protect a range of addresses
+/// (e.g., `Interior`) efficiently.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Sorted;
+
+impl<T, M, I> Reclaimer<T, M, I> for Sorted where M: Memory, I: PtrIdentity<T> {
+    fn reclaim(&self, pointers: &Pointers<T, M, I>, retired: &mut Vec<*mut T>) {
+        let snapshot = pointers.snapshot();
+        retired.retain(|p| {
+            let range = I::range(*p);
+            let index = snapshot.partition_point(|a| *a < range.start);
+            let hazardous = index < snapshot.len() && snapshot[index] < range.end;
+            if !hazardous {
+                unsafe { pointers.free(*p); }
+            }
+            hazardous
+        });
+    }
+}
+
+// StaticPointers ________________________________
+
+/// The retired pointers of a thread of a `StaticPointers`.
+#[cfg(not(loom))]
+struct Retired<T, const RETIRED: usize> {
+    pointers: [*mut T; RETIRED],
+    length: usize,
+}
+
+#[cfg(not(loom))]
+impl<T, const RETIRED: usize> Retired<T, RETIRED> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new empty `Retired`.
+    const fn new() -> Self {
+        Retired { pointers: [ptr::null_mut(); RETIRED], length: 0 }
+    }
+}
+
+#[cfg(not(loom))]
+unsafe impl<T, const RETIRED: usize> Send for Retired<T, RETIRED> where T: Send { }
+
+/// A fixed-size collection of hazardous pointers.
+///
+/// Unlike `Pointers`, the hazardous pointers and the retired pointers are stored inline so this
+/// collection can be constructed in a `const` context (e.g., as a `static`) and never allocates
+/// (only the values the pointers refer to are allocated with `Memory`). There are `THREADS`
+/// threads with `DOMAINS` hazardous pointers each, and each thread can hold up to `RETIRED`
+/// retired pointers. The retired pointers of each thread are protected by a lock, so unlike
+/// `Pointers` this collection is `Sync` and may be shared between threads without a wrapper (and
+/// a thread index may be used by several threads at once). The lock is a `std` mutex, so this
+/// collection still requires `std`.
+///
+/// Once the retired pointers of a thread fill its buffer, the non-hazardous pointers are
+/// deallocated. `RETIRED` must be greater than `THREADS * DOMAINS` so there is always room for
+/// another retired pointer after that.
+///
+/// Only the core of the API of `Pointers` is provided: `mark`, `mark_ptr`, `clear`, `hazardous`,
+/// `pending`, `retire`, and `reclaim`. There is no registration, reclaimer, observer, or pointer
+/// identity other than `Address`.
+///
+/// ```
+/// use std::sync::atomic::{Ordering};
+///
+/// use hazard::{AtomicPtr, BoxMemory, Memory, StaticPointers};
+///
+/// static POINTERS: StaticPointers<usize, BoxMemory, 2, 1, 4> = StaticPointers::new(BoxMemory);
+///
+/// let shared = AtomicPtr::new(BoxMemory.allocate(17));
+/// let pointer = POINTERS.mark(0, 0, &shared);
+/// POINTERS.retire(1, shared.swap(BoxMemory.allocate(322), Ordering::AcqRel));
+/// assert_eq!(unsafe { *pointer }, 17);
+/// assert_eq!(POINTERS.reclaim(1).freed, 0);
+///
+/// POINTERS.clear(0, 0);
+/// assert_eq!(POINTERS.reclaim(1).freed, 1);
+/// # POINTERS.retire(1, shared.into_inner());
+/// # POINTERS.reclaim(1);
+/// ```
+///
+/// A `static` is never dropped, so the pointers still retired when the program exits are never
+/// deallocated.
+#[cfg(not(loom))]
+pub struct StaticPointers<T, M, const THREADS: usize, const DOMAINS: usize, const RETIRED: usize>
+where M: Memory {
+    hazardous: [[sync::AtomicPtr<T>; DOMAINS]; THREADS],
+    retired: [Mutex<Retired<T, RETIRED>>; THREADS],
     memory: M,
 }
 
-impl<T, M> Pointers<T, M> where M: Memory {
+#[cfg(not(loom))]
+impl<T, M, const THREADS: usize, const DOMAINS: usize, const RETIRED: usize>
+StaticPointers<T, M, THREADS, DOMAINS, RETIRED> where M: Memory {
     //- Constructors -----------------------------
 
-    /// Constructs a new `Pointers`.
-    ///
-    /// The maximum number of threads is specified by `threads` and the maximum number of hazardous
-    /// pointers per thread is specified by `domains`.
-    ///
-    /// The maximum size lists of retired pointers can grow to is specified by `threshold`. Once a
-    /// list of retired pointers reaches this limit, any pointers that are no longer hazardous are
-    /// removed from the list and the memory they refer to is deallocated.
-    pub fn new(memory: M, threads: usize, domains: usize, threshold: usize) -> Self {
-        let hazardous = (0..threads).map(|_| {
-            (0..domains).map(|_| AtomicPtr::new(ptr::null_mut())).collect()
-        }).collect();
-        let retired = vec![RefCell::new(vec![]); threads];
-        Pointers {
-            hazardous: AlignVec::new(hazardous),
-            retired: AlignVec::new(retired),
-            threshold: threshold,
-            memory: memory,
+    /// Constructs a new `StaticPointers`.
+    pub const fn new(memory: M) -> Self {
+        const {
+            assert!(
+                RETIRED > THREADS * DOMAINS,
+                "RETIRED must be greater than the number of hazardous pointers",
+            );
+        }
+        StaticPointers {
+            hazardous: [const { [const { sync::AtomicPtr::new(ptr::null_mut()) }; DOMAINS] }; THREADS],
+            retired: [const { Mutex::new(Retired::new()) }; THREADS],
+            memory,
         }
     }
 
     //- Accessors --------------------------------
 
+    /// Returns the memory used to deallocate retired pointers.
+    pub fn memory(&self) -> &M {
+        &self.memory
+    }
+
     /// Sets the hazardous pointer for the supplied domain using the supplied thread.
     ///
+    /// See `Pointers::mark`.
+    ///
     /// **Forward progress guarantee:** lock-free.
-    pub fn mark(&self, thread: usize, domain: usize, pointer: &AtomicPtr<T>) -> *mut T {
+    pub fn mark(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: &AtomicPtr<T>
+    ) -> *mut T {
+        let (thread, domain) = (thread.into().0, domain.into().0);
         loop {
             let value = pointer.load(Acquire);
             self.hazardous[thread][domain].store(value, Release);
+            // The sequentially consistent fence orders the store of the hazardous pointer before
+            // the load which validates it, pairing with the fence a reclamation issues before it
+            // loads the hazardous pointers (see `hazardous`).
+            fence(SeqCst);
             if value == pointer.load(Acquire) {
                 return value;
             }
@@ -156,58 +4684,254 @@ impl<T, M> Pointers<T, M> where M: Memory {
     /// Sets the hazardous pointer for the supplied domain using the supplied thread.
     ///
     /// **Forward progress guarantee:** wait-free population oblivious.
-    pub fn mark_ptr(&self, thread: usize, domain: usize, pointer: *mut T) -> *mut T {
+    pub fn mark_ptr(
+        &self, thread: impl Into<Thread>, domain: impl Into<Domain>, pointer: *mut T
+    ) -> *mut T {
+        let (thread, domain) = (thread.into().0, domain.into().0);
         self.hazardous[thread][domain].store(pointer, Release);
+        fence(SeqCst);
         pointer
     }
 
     /// Clears the hazardous pointer for the supplied domain using the supplied thread.
     ///
     /// **Forward progress guarantee:** wait-free population oblivious.
-    pub fn clear(&self, thread: usize, domain: usize) {
+    pub fn clear(&self, thread: impl Into<Thread>, domain: impl Into<Domain>) {
+        let (thread, domain) = (thread.into().0, domain.into().0);
         self.hazardous[thread][domain].store(ptr::null_mut(), Release);
     }
 
     /// Returns whether the supplied pointer is considered hazardous.
     ///
-    /// **Forward progress guarantee:** wait-free bounded (`threads * domains`).
+    /// Pointers are compared using the `Address` pointer identity.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`THREADS * DOMAINS`).
     pub fn hazardous(&self, pointer: *mut T) -> bool {
-        self.hazardous.iter().any(|h| h.iter().any(|p| pointer == p.load(Acquire)))
+        fence(SeqCst);
+        self.scan(pointer)
     }
 
-    fn kill(&self, pointer: *mut T) -> bool {
-        if self.hazardous(pointer) {
-            false
-        } else {
-            unsafe { self.memory.deallocate(pointer); }
-            true
-        }
+    /// Returns whether the supplied pointer is considered hazardous without fencing first.
+    fn scan(&self, pointer: *mut T) -> bool {
+        let range = <Address as PtrIdentity<T>>::range(pointer);
+        self.hazardous.iter().flat_map(|h| h.iter()).any(|h| {
+            range.contains(&<Address as PtrIdentity<T>>::address(h.load(Acquire)))
+        })
+    }
+
+    fn lock(&self, thread: usize) -> MutexGuard<'_, Retired<T, RETIRED>> {
+        self.retired[thread].lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the number of retired pointers for the supplied thread that have not yet been
+    /// reclaimed.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn pending(&self, thread: usize) -> usize {
+        self.lock(thread).length
     }
 
     /// Retires the supplied pointer using the supplied thread.
     ///
-    /// **Forward progress guarantee:** wait-free bounded (`threads * threads`).
+    /// If the retired pointers for the supplied thread fill its buffer, the pointers that are not
+    /// hazardous are deallocated. They are deallocated without holding the lock, so their
+    /// destructors may retire other pointers. If the buffer is full when this method is called
+    /// (e.g., because another thread filled it but has not yet reclaimed it), it is reclaimed
+    /// until there is room for the supplied pointer.
+    ///
+    /// **Forward progress guarantee:** blocking.
     pub fn retire(&self, thread: usize, pointer: *mut T) {
-        let mut retired = self.retired[thread].borrow_mut();
-        retired.push(pointer);
-        if retired.len() >= self.threshold {
-            retired.retain(|p| !self.kill(*p));
+        debug_assert!(!pointer.is_null(), "retired a null pointer");
+        loop {
+            let mut retired = self.lock(thread);
+            let length = retired.length;
+            if length < RETIRED {
+                retired.pointers[length] = pointer;
+                retired.length += 1;
+                if retired.length == RETIRED {
+                    drop(retired);
+                    self.sweep(thread);
+                }
+                return;
+            }
+
+            drop(retired);
+            self.sweep(thread);
+        }
+    }
+
+    /// Deallocates the retired pointers for the supplied thread that are not hazardous.
+    ///
+    /// **Forward progress guarantee:** blocking.
+    pub fn reclaim(&self, thread: usize) -> ReclaimReport {
+        self.sweep(thread)
+    }
+
+    /// Deallocates the retired pointers of the supplied thread that are not hazardous.
+    ///
+    /// The retired pointers are taken out of the lock while they are checked and deallocated, and
+    /// any pointers retired in the meantime are retired again afterwards.
+    fn sweep(&self, thread: usize) -> ReclaimReport {
+        let mut retired = mem::replace(&mut *self.lock(thread), Retired::new());
+        let mut report = ReclaimReport::default();
+        fence(SeqCst);
+        for index in 0..retired.length {
+            let pointer = retired.pointers[index];
+            if self.scan(pointer) {
+                retired.pointers[report.retained] = pointer;
+                report.retained += 1;
+            } else {
+                unsafe {
+                    poison(pointer);
+                    self.memory.deallocate(pointer);
+                }
+                report.freed += 1;
+            }
+        }
+        retired.length = report.retained;
+
+        let pushed = mem::replace(&mut *self.lock(thread), retired);
+        for pointer in &pushed.pointers[..pushed.length] {
+            self.retire(thread, *pointer);
         }
+        report
     }
 }
 
-impl<T, M> Drop for Pointers<T, M> where M: Memory {
+#[cfg(not(loom))]
+impl<T, M, const THREADS: usize, const DOMAINS: usize, const RETIRED: usize> Drop
+for StaticPointers<T, M, THREADS, DOMAINS, RETIRED> where M: Memory {
     fn drop(&mut self) {
-        for retired in &*self.retired {
-            for pointer in &*retired.borrow() {
-                unsafe { self.memory.deallocate(*pointer); }
+        for retired in &mut self.retired {
+            let retired = retired.get_mut().unwrap_or_else(|e| e.into_inner());
+            for pointer in &retired.pointers[..retired.length] {
+                unsafe {
+                    poison(*pointer);
+                    self.memory.deallocate(*pointer);
+                }
             }
         }
     }
 }
 
-impl<T, M> fmt::Debug for Pointers<T, M> where M: Memory {
+#[cfg(not(loom))]
+impl<T, M, const THREADS: usize, const DOMAINS: usize, const RETIRED: usize> fmt::Debug
+for StaticPointers<T, M, THREADS, DOMAINS, RETIRED> where M: Memory {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.debug_struct("Pointers").field("hazardous", &self.hazardous).finish()
+        formatter.debug_struct("StaticPointers").field("hazardous", &self.hazardous).finish()
+    }
+}
+
+// Thread ________________________________________
+
+/// The index of a thread of a `Pointers`.
+///
+/// See `Domain`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Thread(pub usize);
+
+impl From<usize> for Thread {
+    fn from(thread: usize) -> Self {
+        Thread(thread)
+    }
+}
+
+// ThreadView ____________________________________
+
+/// A view of a single thread of a `Pointers`.
+///
+/// A `ThreadView` borrows the retired pointers of its thread so it must not be used while that
+/// thread is retiring pointers.
+pub struct ThreadView<'p, T, M, I=Address>
+where T: 'p, M: 'p + Memory, I: 'p + PtrIdentity<T> {
+    pointers: &'p Pointers<T, M, I>,
+    thread: usize,
+}
+
+impl<'p, T, M, I> ThreadView<'p, T, M, I> where M: Memory, I: PtrIdentity<T> {
+    //- Accessors --------------------------------
+
+    /// Returns the thread this view is of.
+    pub fn thread(&self) -> usize {
+        self.thread
+    }
+
+    /// Returns the number of retired pointers for this thread that have not yet been reclaimed.
+    ///
+    /// **Forward progress guarantee:** wait-free population oblivious.
+    pub fn pending(&self) -> usize {
+        self.pointers.pending(self.thread)
+    }
+
+    /// Deallocates the retired pointers for this thread that are not hazardous.
+    ///
+    /// **Forward progress guarantee:** that of the reclaimer.
+    pub fn reclaim(&self) -> ReclaimReport {
+        self.pointers.reclaim(self.thread)
+    }
+
+    /// Removes and returns the retired pointers for this thread that are not hazardous.
+    ///
+    /// The returned pointers are not deallocated; the caller is responsible for deallocating them.
+    ///
+    /// **Forward progress guarantee:** wait-free bounded (`threads * domains * retired`).
+    pub fn drain_reclaimable(&self) -> Vec<*mut T> {
+        self.pointers.drain_reclaimable(self.thread)
+    }
+}
+
+impl<'p, T, M, I> fmt::Debug for ThreadView<'p, T, M, I>
+where M: Memory, I: PtrIdentity<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("ThreadView").field("thread", &self.thread).finish()
+    }
+}
+
+// Versioned _____________________________________
+
+/// A pointer along with the generation of its address when it was versioned.
+///
+/// See `AbaGuard`.
+pub struct Versioned<T> {
+    pointer: *mut T,
+    generation: usize,
+}
+
+impl<T> Versioned<T> {
+    //- Accessors --------------------------------
+
+    /// Returns the pointer.
+    pub fn pointer(&self) -> *mut T {
+        self.pointer
+    }
+
+    /// Returns the generation of the address of the pointer when it was versioned.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
+impl<T> Clone for Versioned<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Versioned<T> { }
+
+impl<T> PartialEq for Versioned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pointer == other.pointer && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Versioned<T> { }
+
+impl<T> fmt::Debug for Versioned<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Versioned")
+            .field("pointer", &self.pointer)
+            .field("generation", &self.generation)
+            .finish()
     }
 }
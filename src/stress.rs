@@ -0,0 +1,209 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Stress testing for structures built on hazardous pointers.
+//!
+//! `stress` runs an operation on several threads at once against a shared `Pointers` whose nodes
+//! are allocated with a `CountingMemory`. A node that is deallocated twice, deallocated without
+//! having been allocated, or leaked causes a panic, and so does reading a node that has been
+//! deallocated (the memory of deallocated nodes is overwritten before it is freed).
+//!
+//! ```
+//! use hazard::stress;
+//!
+//! let swaps = unsafe { stress::stress(4, 1, 8, 1_000, stress::swap) };
+//! assert!(swaps <= 4 * 1_000);
+//! ```
+//!
+//! This module is only available if the `test-util` feature is enabled.
+
+use std::mem;
+use std::ptr;
+use std::thread;
+use std::collections::{HashSet};
+use std::sync::{Mutex};
+use std::sync::atomic::{AtomicUsize};
+use std::sync::atomic::Ordering::*;
+
+use super::{AtomicPtr, Memory, Pointers, POISON};
+
+//================================================
+// Constants
+//================================================
+
+/// The value of the sentinel of a `Node` that has not been deallocated.
+pub const LIVE: usize = 0x1117_1117;
+
+//================================================
+// Structs
+//================================================
+
+// CountingMemory ________________________________
+
+/// An allocator that uses `Box` and checks that every allocation is deallocated exactly once.
+///
+/// The memory of each allocation is overwritten with `POISON` after its value is dropped and
+/// before it is deallocated.
+#[derive(Debug, Default)]
+pub struct CountingMemory {
+    live: Mutex<HashSet<usize>>,
+    allocated: AtomicUsize,
+    deallocated: AtomicUsize,
+}
+
+impl CountingMemory {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `CountingMemory`.
+    pub fn new() -> Self {
+        CountingMemory::default()
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the number of allocations.
+    pub fn allocated(&self) -> usize {
+        self.allocated.load(Relaxed)
+    }
+
+    /// Returns the number of deallocations.
+    pub fn deallocated(&self) -> usize {
+        self.deallocated.load(Relaxed)
+    }
+
+    /// Returns the number of allocations that have not been deallocated.
+    pub fn outstanding(&self) -> usize {
+        self.live.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl Memory for CountingMemory {
+    fn allocate<T>(&self, value: T) -> *mut T {
+        let pointer = Box::into_raw(Box::new(value));
+        self.live.lock().unwrap_or_else(|e| e.into_inner()).insert(pointer as usize);
+        self.allocated.fetch_add(1, Relaxed);
+        pointer
+    }
+
+    unsafe fn deallocate<T>(&self, pointer: *mut T) {
+        let live = self.live.lock().unwrap_or_else(|e| e.into_inner()).remove(&(pointer as usize));
+        assert!(live, "deallocated {:?} which is not allocated (double free?)", pointer);
+        self.deallocated.fetch_add(1, Relaxed);
+        ptr::drop_in_place(pointer);
+        ptr::write_bytes(pointer as *mut u8, POISON, mem::size_of::<T>());
+        drop(Box::from_raw(pointer as *mut mem::ManuallyDrop<T>));
+    }
+}
+
+// Node __________________________________________
+
+/// A value which detects whether it is read after it has been deallocated.
+#[derive(Copy, Clone, Debug)]
+pub struct Node {
+    sentinel: usize,
+    value: usize,
+}
+
+impl Node {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `Node`.
+    pub fn new(value: usize) -> Self {
+        Node { sentinel: LIVE, value }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the value of this node.
+    ///
+    /// # Panics
+    ///
+    /// This panics if this node has been deallocated by a `CountingMemory`.
+    pub fn value(&self) -> usize {
+        let sentinel = unsafe { ptr::read_volatile(&self.sentinel) };
+        assert_eq!(sentinel, LIVE, "read a node after it was deallocated (use after free)");
+        self.value
+    }
+}
+
+// Shared ________________________________________
+
+/// A collection shared between the threads of `stress`.
+struct Shared<'p, 'm>(&'p Pointers<Node, &'m CountingMemory>);
+
+unsafe impl<'p, 'm> Sync for Shared<'p, 'm> { }
+
+//================================================
+// Functions
+//================================================
+
+/// Runs the supplied operation the supplied number of times on each of the supplied number of
+/// threads at once and returns the value of the final node.
+///
+/// The operation is supplied the index of its thread, a collection with the supplied number of
+/// domains per thread and the supplied threshold, and an atomic pointer that is shared by every
+/// thread and initially points to a node with a value of `0`. Once every thread has finished, the
+/// collection is dropped and every node must have been deallocated exactly once.
+///
+/// # Panics
+///
+/// This panics if a node is deallocated more than once, read after it is deallocated, or leaked.
+///
+/// # Safety
+///
+/// The operation must only use the supplied thread index with the collection and must leave the
+/// atomic pointer pointing to a valid node allocated with the memory of the collection.
+pub unsafe fn stress<F>(
+    threads: usize, domains: usize, threshold: usize, iterations: usize, operation: F
+) -> usize where F: Fn(usize, &Pointers<Node, &CountingMemory>, &AtomicPtr<Node>) + Sync {
+    let memory = CountingMemory::new();
+    let pointers = Pointers::new(&memory, threads, domains, threshold);
+    let pointer = AtomicPtr::new(memory.allocate(Node::new(0)));
+
+    let shared = &Shared(&pointers);
+    thread::scope(|scope| {
+        for thread in 0..threads {
+            let (operation, pointer) = (&operation, &pointer);
+            scope.spawn(move || {
+                for _ in 0..iterations {
+                    operation(thread, shared.0, pointer);
+                }
+            });
+        }
+    });
+
+    drop(pointers);
+    let pointer = pointer.into_inner();
+    let value = (*pointer).value();
+    memory.deallocate(pointer);
+    assert_eq!(memory.outstanding(), 0, "leaked {} nodes", memory.outstanding());
+    value
+}
+
+/// Replaces the node the supplied atomic pointer points to with a node with the next value and
+/// retires the previous node.
+///
+/// This protects the current node with the first domain of the supplied thread.
+pub fn swap(thread: usize, pointers: &Pointers<Node, &CountingMemory>, pointer: &AtomicPtr<Node>) {
+    let memory = *pointers.memory();
+    let current = pointers.mark(thread, 0, pointer);
+    let new = memory.allocate(Node::new(unsafe { (*current).value() } + 1));
+    let swapped = pointer.compare_exchange(current, new, AcqRel, Acquire).is_ok();
+    pointers.clear(thread, 0);
+    if swapped {
+        pointers.retire(thread, current);
+    } else {
+        unsafe { memory.deallocate(new); }
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The atomic types and fences used by the bookkeeping of the collections.
+//!
+//! These are those of `std` unless the `portable-atomic` feature is enabled, in which case they
+//! are those of `portable-atomic` (which fall back to a software implementation on targets without
+//! native atomic operations). `AtomicPtr` and `AtomicUsize` are re-exported at the root of the
+//! crate since the atomic pointers and words supplied by users must be of the same types.
+//!
+//! When built with `--cfg loom`, these are those of `loom` so that `tests/loom.rs` can check the
+//! orderings used by `Pointers`.
+
+#[cfg(all(not(loom), not(feature="portable-atomic")))]
+pub use std::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize};
+#[cfg(all(not(loom), feature="portable-atomic"))]
+pub use portable_atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize};
+#[cfg(loom)]
+pub use loom::sync::atomic::{fence, AtomicBool, AtomicPtr, AtomicUsize};
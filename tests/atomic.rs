@@ -0,0 +1,104 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate hazard;
+
+use std::sync::atomic::{AtomicUsize};
+use std::sync::atomic::Ordering::*;
+
+use hazard::*;
+use hazard::atomic::{AtomicHazardPtr};
+
+//================================================
+// Structs
+//================================================
+
+// Value _________________________________________
+
+/// A value which counts how many times it has been dropped.
+struct Value<'d>(usize, &'d AtomicUsize);
+
+impl<'d> Drop for Value<'d> {
+    fn drop(&mut self) {
+        self.1.fetch_add(1, SeqCst);
+    }
+}
+
+//================================================
+// Tests
+//================================================
+
+#[test]
+fn null_loads_nothing() {
+    let pointers = Pointers::<usize, _>::new(BoxMemory, 1, 1, 8);
+    let shared = AtomicHazardPtr::null(&pointers);
+    assert!(unsafe { shared.load_protected(0, 0) }.is_none());
+
+    shared.store(0, 1);
+    assert_eq!(*unsafe { shared.load_protected(0, 0) }.unwrap(), 1);
+}
+
+#[test]
+fn store_retires_previous() {
+    let dropped = AtomicUsize::new(0);
+    let pointers = Pointers::new(BoxMemory, 1, 1, 1024);
+    let shared = AtomicHazardPtr::new(&pointers, Value(0, &dropped));
+
+    shared.store(0, Value(1, &dropped));
+    assert_eq!(pointers.pending(0), 1);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert_eq!(dropped.load(SeqCst), 1);
+    assert_eq!(unsafe { shared.load_protected(0, 0) }.unwrap().0, 1);
+
+    drop(shared);
+    assert_eq!(dropped.load(SeqCst), 2);
+}
+
+#[test]
+fn compare_and_set_replaces_current() {
+    let dropped = AtomicUsize::new(0);
+    let pointers = Pointers::new(BoxMemory, 1, 2, 1024);
+    let shared = AtomicHazardPtr::new(&pointers, Value(0, &dropped));
+
+    let current = unsafe { shared.load_protected(0, 0) }.unwrap();
+    assert!(shared.compare_and_set(0, current.as_ptr(), Value(1, &dropped)));
+    assert_eq!(current.0, 0);
+
+    // The previous value is protected until its reference is dropped.
+    assert_eq!(pointers.reclaim(0).freed, 0);
+    drop(current);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert_eq!(dropped.load(SeqCst), 1);
+    assert_eq!(unsafe { shared.load_protected(0, 1) }.unwrap().0, 1);
+}
+
+#[test]
+fn compare_and_set_fails_on_stale() {
+    let dropped = AtomicUsize::new(0);
+    let pointers = Pointers::new(BoxMemory, 1, 2, 1024);
+    let shared = AtomicHazardPtr::new(&pointers, Value(0, &dropped));
+
+    let stale = unsafe { shared.load_protected(0, 0) }.unwrap();
+    shared.store(0, Value(1, &dropped));
+
+    // The supplied value is dropped immediately and nothing is retired.
+    assert!(!shared.compare_and_set(0, stale.as_ptr(), Value(2, &dropped)));
+    assert_eq!(dropped.load(SeqCst), 1);
+    assert_eq!(pointers.pending(0), 1);
+    assert_eq!(unsafe { shared.load_protected(0, 1) }.unwrap().0, 1);
+
+    drop(stale);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert_eq!(dropped.load(SeqCst), 2);
+}
@@ -0,0 +1,143 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Model checks of the orderings used by `Pointers`.
+//!
+//! These are only built with `RUSTFLAGS="--cfg loom" cargo test --test loom --release`.
+
+#![cfg(loom)]
+
+extern crate hazard;
+extern crate loom;
+
+use std::ptr;
+use std::sync::{Arc};
+use std::sync::atomic::{AtomicBool};
+use std::sync::atomic::Ordering::*;
+
+use hazard::*;
+use loom::sync::atomic::{AtomicPtr};
+
+//================================================
+// Structs
+//================================================
+
+// Leaking _______________________________________
+
+/// A memory which drops values without deallocating them so that reading a reclaimed value is
+/// observable rather than undefined.
+#[derive(Copy, Clone, Debug, Default)]
+struct Leaking;
+
+impl Memory for Leaking {
+    fn allocate<T>(&self, value: T) -> *mut T {
+        Box::into_raw(Box::new(value))
+    }
+
+    unsafe fn deallocate<T>(&self, pointer: *mut T) {
+        ptr::drop_in_place(pointer);
+    }
+}
+
+// Shared ________________________________________
+
+/// A collection and an atomic pointer shared between the threads of a model.
+struct Shared {
+    pointers: Pointers<Value, Leaking>,
+    pointer: AtomicPtr<Value>,
+}
+
+unsafe impl Send for Shared { }
+unsafe impl Sync for Shared { }
+
+// Value _________________________________________
+
+/// A value which records that it has been dropped.
+///
+/// The flag is not modeled by `loom`, so a load always observes a preceding drop in the order in
+/// which the model runs the threads.
+struct Value {
+    dropped: AtomicBool,
+}
+
+impl Value {
+    fn new() -> Self {
+        Value { dropped: AtomicBool::new(false) }
+    }
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        self.dropped.store(true, SeqCst);
+    }
+}
+
+//================================================
+// Tests
+//================================================
+
+#[test]
+fn mark_and_retire() {
+    let mut builder = loom::model::Builder::new();
+    builder.preemption_bound = Some(3);
+    builder.check(|| {
+        let shared = Arc::new(Shared {
+            pointers: Pointers::new(Leaking, 2, 1, 1),
+            pointer: AtomicPtr::new(Leaking.allocate(Value::new())),
+        });
+
+        let reader = shared.clone();
+        let thread = loom::thread::spawn(move || {
+            let value = reader.pointers.mark(1, 0, &reader.pointer);
+            assert!(!unsafe { (*value).dropped.load(SeqCst) });
+            reader.pointers.clear(1, 0);
+        });
+
+        // The threshold is one so the swapped value is reclaimed as soon as it is retired.
+        let value = shared.pointer.swap(Leaking.allocate(Value::new()), SeqCst);
+        shared.pointers.retire(0, value);
+        thread.join().unwrap();
+
+        shared.pointers.reclaim_all();
+        shared.pointers.retire(0, shared.pointer.load(SeqCst));
+    });
+}
+
+#[test]
+fn mark_and_retire_synchronized() {
+    let mut builder = loom::model::Builder::new();
+    builder.preemption_bound = Some(3);
+    builder.check(|| {
+        let shared = Arc::new(Shared {
+            pointers: Pointers::new(Leaking, 2, 1, 1),
+            pointer: AtomicPtr::new(Leaking.allocate(Value::new())),
+        });
+
+        let reader = shared.clone();
+        let thread = loom::thread::spawn(move || {
+            let value = reader.pointers.mark(1, 0, &reader.pointer);
+            assert!(!unsafe { (*value).dropped.load(SeqCst) });
+            reader.pointers.clear(1, 0);
+        });
+
+        // The unlinking swap is only acquire-release; the fence of `retire_synchronized` orders
+        // it before the loads of the hazardous pointers.
+        let value = shared.pointer.swap(Leaking.allocate(Value::new()), AcqRel);
+        shared.pointers.retire_synchronized(0, value);
+        thread.join().unwrap();
+
+        shared.pointers.reclaim_all();
+        shared.pointers.retire(0, shared.pointer.load(SeqCst));
+    });
+}
@@ -0,0 +1,1231 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate hazard;
+
+use std::alloc::{self, Layout};
+use std::mem;
+use std::ptr::{self, NonNull};
+use std::slice;
+use std::thread;
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc};
+use std::sync::{Arc};
+#[cfg(feature="async")]
+use std::future::{self, Future};
+#[cfg(feature="async")]
+use std::pin::{pin};
+#[cfg(feature="async")]
+use std::task::{Context, Poll, Wake, Waker};
+#[cfg(feature="async")]
+use std::time::{Duration};
+use std::sync::atomic::{AtomicUsize};
+use std::sync::atomic::Ordering::*;
+
+use hazard::*;
+
+//================================================
+// Structs
+//================================================
+
+// Counting ______________________________________
+
+/// A memory which counts allocations and deallocations.
+#[derive(Debug, Default)]
+struct Counting {
+    allocated: AtomicUsize,
+    deallocated: AtomicUsize,
+}
+
+impl Counting {
+    fn live(&self) -> usize {
+        self.allocated.load(SeqCst) - self.deallocated.load(SeqCst)
+    }
+}
+
+impl Memory for Counting {
+    fn allocate<T>(&self, value: T) -> *mut T {
+        self.allocated.fetch_add(1, SeqCst);
+        BoxMemory.allocate(value)
+    }
+
+    unsafe fn deallocate<T>(&self, pointer: *mut T) {
+        self.deallocated.fetch_add(1, SeqCst);
+        BoxMemory.deallocate(pointer);
+    }
+}
+
+impl LayoutMemory for Counting {
+    unsafe fn deallocate_layout(&self, pointer: *mut u8, layout: Layout) {
+        self.deallocated.fetch_add(1, SeqCst);
+        BoxMemory.deallocate_layout(pointer, layout);
+    }
+}
+
+// Shared ________________________________________
+
+/// A collection shared between the threads of a test.
+struct Shared<T>(Pointers<T, BoxMemory>);
+
+unsafe impl<T> Send for Shared<T> where T: Send { }
+unsafe impl<T> Sync for Shared<T> where T: Send { }
+
+// Chain _________________________________________
+
+/// A link in a chain which retires the next link when it is dropped.
+struct Chain {
+    next: *mut Chain,
+    pointers: *const Pointers<Chain, BoxMemory>,
+}
+
+impl Drop for Chain {
+    fn drop(&mut self) {
+        if !self.next.is_null() {
+            unsafe { (*self.pointers).retire(0, self.next); }
+        }
+    }
+}
+
+// Node __________________________________________
+
+/// A node which retires its child intrusively when it is dropped.
+struct Node {
+    link: AtomicPtr<Node>,
+    child: *mut Node,
+    pointers: *const Pointers<Node, BoxMemory>,
+}
+
+unsafe impl Retirable for Node {
+    fn retire_link(&self) -> &AtomicPtr<Node> {
+        &self.link
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        if !self.child.is_null() {
+            unsafe { (*self.pointers).retire_intrusive(0, self.child); }
+        }
+    }
+}
+
+// Retrying _____________________________________
+
+/// A value which tries to retire another value using the same thread when it is dropped.
+struct Retrying {
+    other: *mut Retrying,
+    pointers: *const Pointers<Retrying, BoxMemory>,
+    result: *const Cell<Option<Result<(), PointersError>>>,
+}
+
+impl Drop for Retrying {
+    fn drop(&mut self) {
+        if !self.other.is_null() {
+            unsafe { (*self.result).set(Some((*self.pointers).try_retire(0, self.other))); }
+        }
+    }
+}
+
+// Events ________________________________________
+
+/// A reclamation event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Event {
+    Retired(usize, usize),
+    Reclaimed(usize, ReclaimReport),
+}
+
+/// An observer which records reclamation events.
+#[derive(Clone, Default)]
+struct Events(Rc<RefCell<Vec<Event>>>);
+
+impl<T> Observer<T> for Events {
+    fn retired(&self, thread: usize, _: *mut T, pending: usize) {
+        self.0.borrow_mut().push(Event::Retired(thread, pending));
+    }
+
+    fn reclaimed(&self, thread: usize, report: ReclaimReport) {
+        self.0.borrow_mut().push(Event::Reclaimed(thread, report));
+    }
+}
+
+// Ignore ________________________________________
+
+/// A waker which ignores wakeups.
+#[cfg(feature="async")]
+struct Ignore;
+
+#[cfg(feature="async")]
+impl Wake for Ignore {
+    fn wake(self: Arc<Self>) { }
+}
+
+//================================================
+// Functions
+//================================================
+
+/// Allocates a string followed by a buffer, which is deallocated with the layout of both.
+fn allocate_layout(memory: &Counting, value: &str) -> (*mut String, Layout) {
+    let layout = Layout::new::<String>().extend(Layout::new::<[u8; 64]>()).unwrap().0;
+    let pointer = unsafe { alloc::alloc(layout) } as *mut String;
+    unsafe { pointer.write(String::from(value)); }
+    memory.allocated.fetch_add(1, SeqCst);
+    (pointer, layout)
+}
+
+//================================================
+// Tests
+//================================================
+
+#[test]
+fn consecutive_duplicate_retire_ignored() {
+    let memory = Counting::default();
+    let pointers = Pointers::new(&memory, 1, 1, 8);
+    let first = memory.allocate(17);
+    let second = memory.allocate(322);
+    pointers.retire(0, first);
+    pointers.retire(0, first);
+    pointers.retire(0, second);
+    assert_eq!(pointers.pending(0), 2);
+    assert_eq!(pointers.reclaim(0).freed, 2);
+    assert_eq!(memory.live(), 0);
+}
+
+#[test]
+fn zero_sized_retires_not_ignored() {
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Value;
+
+    impl Drop for Value {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, SeqCst);
+        }
+    }
+
+    let pointers = Pointers::new(BoxMemory, 1, 1, 8);
+    for _ in 0..4 {
+        pointers.retire(0, BoxMemory.allocate(Value));
+    }
+    assert_eq!(pointers.pending(0), 4);
+    assert_eq!(pointers.reclaim(0).freed, 4);
+    assert_eq!(DROPPED.load(SeqCst), 4);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "hazard is still active")]
+fn drop_with_hazard_panics() {
+    let pointers = Pointers::new(BoxMemory, 2, 2, 8);
+    let value = BoxMemory.allocate(17);
+    pointers.mark_ptr(1, 1, value);
+    pointers.retire(0, value);
+    drop(pointers);
+}
+
+#[test]
+fn drop_without_hazards() {
+    let memory = Counting::default();
+    let pointers = Pointers::new(&memory, 2, 2, 8);
+    let value = memory.allocate(17);
+    pointers.mark_ptr(1, 1, value);
+    pointers.retire(0, value);
+    assert_eq!(pointers.hazardous_iter().collect::<Vec<_>>(), [value]);
+    pointers.clear(1, 1);
+    assert_eq!(pointers.hazardous_iter().count(), 0);
+    drop(pointers);
+    assert_eq!(memory.live(), 0);
+}
+
+#[test]
+fn reclaimed_values_poisoned() {
+    /// A memory which records the bytes of each value just before deallocating it.
+    #[derive(Default)]
+    struct Inspecting(RefCell<Vec<Vec<u8>>>);
+
+    impl Memory for Inspecting {
+        fn allocate<T>(&self, value: T) -> *mut T {
+            BoxMemory.allocate(value)
+        }
+
+        unsafe fn deallocate<T>(&self, pointer: *mut T) {
+            if !mem::needs_drop::<T>() {
+                let bytes = slice::from_raw_parts(pointer as *const u8, mem::size_of::<T>());
+                self.0.borrow_mut().push(bytes.to_vec());
+            }
+            BoxMemory.deallocate(pointer);
+        }
+    }
+
+    let memory = Inspecting::default();
+    let pointers = PointersBuilder::new(&memory, 1, 1, 8).reclaimer(BatchScan).build();
+    pointers.retire(0, memory.allocate(17u64));
+    pointers.retire(0, memory.allocate(322u64));
+    assert_eq!(pointers.reclaim(0).freed, 2);
+
+    let expected = if cfg!(debug_assertions) {
+        [[POISON; 8]; 2]
+    } else {
+        [17u64.to_ne_bytes(), 322u64.to_ne_bytes()]
+    };
+    assert_eq!(*memory.0.borrow(), expected);
+}
+
+#[test]
+fn forget_on_drop_leaks_retired() {
+    let memory = Counting::default();
+    let pointers = Pointers::new(&memory, 2, 1, 8);
+    pointers.set_forget_on_drop(true);
+    let retired = (0..3).map(|v| memory.allocate(v)).collect::<Vec<_>>();
+    pointers.mark_ptr(1, 0, retired[0]);
+    for pointer in &retired {
+        pointers.retire(0, *pointer);
+    }
+
+    // Neither the retired pointers nor the hazardous pointer are touched.
+    drop(pointers);
+    assert_eq!(memory.live(), 3);
+    for pointer in retired {
+        unsafe { memory.deallocate(pointer); }
+    }
+}
+
+#[test]
+fn scan_interval_reclaims_below_threshold() {
+    let memory = Counting::default();
+    let pointers = Pointers::new(&memory, 2, 1, 100);
+    pointers.set_scan_interval(4);
+    let hazardous = memory.allocate(0);
+    pointers.mark_ptr(1, 0, hazardous);
+    pointers.retire(0, hazardous);
+
+    // Every fourth retire reclaims the retired pointers, which are far below the threshold.
+    for value in 1..4 {
+        pointers.retire(0, memory.allocate(value));
+    }
+    assert_eq!(pointers.pending(0), 1);
+    for value in 4..7 {
+        pointers.retire(0, memory.allocate(value));
+    }
+    assert_eq!(pointers.pending(0), 4);
+    pointers.retire(0, memory.allocate(7));
+    assert_eq!(pointers.pending(0), 1);
+
+    pointers.set_scan_interval(0);
+    for value in 8..16 {
+        pointers.retire(0, memory.allocate(value));
+    }
+    assert_eq!(pointers.pending(0), 9);
+    pointers.clear(1, 0);
+}
+
+#[test]
+fn last_sweep_freed_tracks_each_thread() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 8);
+    assert_eq!(pointers.last_sweep_freed(0), None);
+    assert_eq!(pointers.last_sweep_freed(1), None);
+
+    let hazardous = BoxMemory.allocate(0);
+    pointers.mark_ptr(1, 0, hazardous);
+    pointers.retire(0, hazardous);
+    pointers.retire(0, BoxMemory.allocate(1));
+    pointers.retire(0, BoxMemory.allocate(2));
+    pointers.reclaim(0);
+    assert_eq!(pointers.last_sweep_freed(0), Some(2));
+    assert_eq!(pointers.last_sweep_freed(1), None);
+
+    // A sweep which frees nothing is recorded too.
+    pointers.reclaim(0);
+    assert_eq!(pointers.last_sweep_freed(0), Some(0));
+    pointers.clear(1, 0);
+    pointers.reclaim(0);
+    assert_eq!(pointers.last_sweep_freed(0), Some(1));
+}
+
+#[test]
+fn sample_hazards_uniform_across_domains() {
+    let mut pointers = Pointers::new(BoxMemory, 1, 1, 8);
+    assert_eq!(pointers.sample_hazards(0), 0.0);
+    assert_eq!(pointers.merge(Pointers::new(BoxMemory, 1, 7, 8)), 1);
+
+    // Seven of the eight hazardous pointers are set but only one of the two threads has any.
+    let value = BoxMemory.allocate(17);
+    for domain in 0..7 {
+        pointers.mark_ptr(1, domain, value);
+    }
+    let estimate = pointers.sample_hazards(10_000);
+    assert!((estimate - 0.875).abs() < 0.05, "estimate is {}", estimate);
+
+    pointers.clear_all(1);
+    assert_eq!(pointers.sample_hazards(100), 0.0);
+    pointers.retire(0, value);
+}
+
+#[test]
+fn mark_ptr_prev_returns_previous() {
+    let pointers = Pointers::new(BoxMemory, 1, 2, 8);
+    let first = BoxMemory.allocate(17);
+    let second = BoxMemory.allocate(322);
+    assert_eq!(pointers.mark_ptr_prev(0, 0, first), ptr::null_mut());
+    assert_eq!(pointers.mark_ptr_prev(0, 0, second), first);
+    assert_eq!(pointers.mark_ptr_prev(0, 1, first), ptr::null_mut());
+    assert_eq!(pointers.hazardous_iter().collect::<Vec<_>>(), [second, first]);
+
+    // Replacing a hazardous pointer with null clears the domain and returns what it protected.
+    pointers.retire(0, second);
+    assert_eq!(pointers.mark_ptr_prev(0, 0, ptr::null_mut()), second);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    pointers.clear(0, 1);
+    pointers.retire(0, first);
+}
+
+#[test]
+fn retire_box_reclaimed() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 8);
+    let boxed = Box::new(String::from("hazardous"));
+    pointers.mark_ptr(1, 0, &*boxed as *const String as *mut String);
+    pointers.retire_box(0, boxed);
+    pointers.retire_box(0, Box::new(String::from("reclaimed")));
+    assert_eq!(pointers.reclaim(0), ReclaimReport { freed: 1, retained: 1 });
+    assert_eq!(unsafe { &*pointers.hazards_of(1)[0] }, "hazardous");
+    pointers.clear(1, 0);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+}
+
+#[test]
+fn retire_boxes_checks_threshold_once() {
+    let mut pointers = Pointers::new(BoxMemory, 1, 1, 4);
+    let events = Events::default();
+    pointers.set_observer(events.clone());
+    pointers.retire_boxes(0, (0..6).map(Box::new));
+    assert_eq!(pointers.pending(0), 0);
+
+    // Every box is retired before the retired pointers are reclaimed once.
+    let mut expected = (1..7).map(|p| Event::Retired(0, p)).collect::<Vec<_>>();
+    expected.push(Event::Reclaimed(0, ReclaimReport { freed: 6, retained: 0 }));
+    assert_eq!(*events.0.borrow(), expected);
+
+    pointers.retire_boxes(0, (0..3).map(Box::new));
+    assert_eq!(pointers.pending(0), 3);
+    pointers.retire_boxes(0, vec![]);
+    assert_eq!(pointers.pending(0), 3);
+}
+
+#[test]
+fn registry_queries_every_collection() {
+    let strings = Pointers::new(BoxMemory, 1, 1, 8);
+    let numbers = Pointers::new(BoxMemory, 1, 1, 8);
+    let string = BoxMemory.allocate(String::from("hazardous"));
+    let number = BoxMemory.allocate(17u64);
+
+    let mut registry = HazardRegistry::new();
+    assert!(!registry.is_address_hazardous(string as *const ()));
+    registry.register(&strings);
+    registry.register(&numbers);
+    strings.mark_ptr(0, 0, string);
+    numbers.mark_ptr(0, 0, number);
+    assert!(registry.is_address_hazardous(string as *const ()));
+    assert!(registry.is_address_hazardous(number as *const ()));
+
+    numbers.clear(0, 0);
+    assert!(!registry.is_address_hazardous(number as *const ()));
+    assert!(registry.is_address_hazardous(string as *const ()));
+    strings.clear(0, 0);
+    assert!(!registry.is_address_hazardous(string as *const ()));
+    strings.retire(0, string);
+    numbers.retire(0, number);
+}
+
+#[test]
+fn try_variants_report_errors() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 8);
+    let value = BoxMemory.allocate(17);
+    let atomic = AtomicPtr::new(value);
+    assert_eq!(pointers.try_mark(2, 0, &atomic), Err(PointersError::Thread(2)));
+    assert_eq!(pointers.try_mark(1, 1, &atomic), Err(PointersError::Domain(1)));
+    assert_eq!(pointers.try_clear(2, 0), Err(PointersError::Thread(2)));
+    assert_eq!(pointers.try_clear(1, 1), Err(PointersError::Domain(1)));
+    assert_eq!(pointers.try_retire(2, value), Err(PointersError::Thread(2)));
+    assert_eq!(pointers.pending(0) + pointers.pending(1), 0);
+
+    assert_eq!(pointers.try_mark(1, 0, &atomic), Ok(value));
+    assert_eq!(pointers.try_retire(0, value), Ok(()));
+    assert_eq!(pointers.reclaim(0).retained, 1);
+    assert_eq!(pointers.try_clear(1, 0), Ok(()));
+    assert_eq!(pointers.reclaim(0).freed, 1);
+}
+
+#[test]
+fn try_retire_during_reclamation_borrowed() {
+    let pointers = Pointers::new(BoxMemory, 1, 1, 8);
+    let result = Cell::new(None);
+    let other = BoxMemory.allocate(Retrying {
+        other: ptr::null_mut(),
+        pointers: &pointers,
+        result: &result,
+    });
+    let retrying = Retrying { other, pointers: &pointers, result: &result };
+    pointers.retire(0, BoxMemory.allocate(retrying));
+    assert_eq!(pointers.reclaim(0).freed, 1);
+
+    // The failed retire leaves the pointer with the caller.
+    assert_eq!(result.get(), Some(Err(PointersError::Borrowed(0))));
+    assert_eq!(pointers.pending(0), 0);
+    pointers.retire(0, other);
+}
+
+#[test]
+fn fork_shares_memory() {
+    let memory = Counting::default();
+    let pointers = Pointers::<String, _>::new(&memory, 1, 1, 8);
+    let fork = pointers.fork::<u64>(2, 2, 4);
+    assert_eq!(fork.hazards_of(1).len(), 2);
+
+    let value = fork.memory().allocate(17u64);
+    fork.mark_ptr(1, 1, value);
+    fork.retire(0, value);
+    pointers.retire(0, memory.allocate(String::from("reclaimed")));
+    assert_eq!(memory.live(), 2);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+
+    // The hazardous pointers of a fork are separate from those of the original collection.
+    assert!(!pointers.hazardous(value as *mut String));
+    assert_eq!(fork.reclaim(0).retained, 1);
+    fork.clear(1, 1);
+    drop(fork);
+    assert_eq!(memory.live(), 0);
+}
+
+#[test]
+fn protect_validated_checks_after_marking() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 8);
+    let first = BoxMemory.allocate(17);
+    let atomic = AtomicPtr::new(first);
+
+    // The validation function sees the hazardous pointer already set.
+    let valid = pointers.protect_validated(1, 0, first, |p| {
+        pointers.hazardous(p) && atomic.load(Acquire) == p
+    });
+    assert!(valid);
+    pointers.retire(0, atomic.swap(BoxMemory.allocate(322), AcqRel));
+    assert_eq!(pointers.reclaim(0).retained, 1);
+
+    // A pointer replaced before it was protected fails validation and must be cleared.
+    assert!(!pointers.protect_validated(1, 0, first, |p| atomic.load(Acquire) == p));
+    pointers.clear(1, 0);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    pointers.retire(0, atomic.into_inner());
+}
+
+#[test]
+fn acquire_domain_finds_unset() {
+    let pointers = Pointers::new(BoxMemory, 2, 3, 8);
+    let value = BoxMemory.allocate(17);
+    assert_eq!(pointers.acquire_domain(0), Some(0));
+    pointers.mark_ptr(0, 0, value);
+    pointers.mark_ptr(0, 2, value);
+    assert_eq!(pointers.acquire_domain(0), Some(1));
+    assert_eq!(pointers.acquire_domain(1), Some(0));
+
+    // The domain is not reserved until it is set.
+    assert_eq!(pointers.acquire_domain(0), Some(1));
+    pointers.mark_ptr(0, 1, value);
+    assert_eq!(pointers.acquire_domain(0), None);
+    pointers.clear(0, 2);
+    assert_eq!(pointers.acquire_domain(0), Some(2));
+    pointers.clear_all(0);
+    pointers.retire(0, value);
+}
+
+#[test]
+fn pin_defers_own_reclamation() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 2);
+    let outer = pointers.pin(0);
+    let inner = pointers.pin(0);
+    for value in 0..4 {
+        pointers.retire(0, BoxMemory.allocate(value));
+        pointers.retire(1, BoxMemory.allocate(value));
+    }
+    assert_eq!(pointers.reclaim(0).freed, 0);
+    assert_eq!(pointers.pending(0), 4);
+    assert_eq!(pointers.pending(1), 0);
+
+    // Pins nest, so the thread stays pinned until every guard is dropped.
+    drop(inner);
+    assert_eq!(pointers.reclaim(0).freed, 0);
+    drop(outer);
+    assert_eq!(pointers.reclaim(0).freed, 4);
+}
+
+#[test]
+fn pin_all_defers_every_reclamation() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 2);
+    let guard = pointers.pin_all(1);
+    for value in 0..4 {
+        pointers.retire(0, BoxMemory.allocate(value));
+        pointers.retire(1, BoxMemory.allocate(value));
+    }
+    assert_eq!(pointers.reclaim(0).freed, 0);
+    assert_eq!(pointers.reclaim(1).freed, 0);
+    assert_eq!(pointers.pending(0) + pointers.pending(1), 8);
+
+    drop(guard);
+    assert_eq!(pointers.reclaim(0).freed, 4);
+    assert_eq!(pointers.reclaim(1).freed, 4);
+}
+
+#[test]
+fn reclaim_if_needed_waits_for_threshold() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 8);
+    let hazardous = BoxMemory.allocate(0);
+    pointers.mark_ptr(1, 0, hazardous);
+    pointers.retire(0, hazardous);
+    for value in 1..4 {
+        pointers.retire(0, BoxMemory.allocate(value));
+    }
+    assert_eq!(pointers.reclaim_if_needed(0), 0);
+    assert_eq!(pointers.pending(0), 4);
+
+    // Lowering the threshold doesn't reclaim anything until a pointer is retired or this is called.
+    pointers.set_threshold(2);
+    assert_eq!(pointers.pending(0), 4);
+    assert_eq!(pointers.reclaim_if_needed(0), 3);
+    assert_eq!(pointers.pending(0), 1);
+    assert_eq!(pointers.reclaim_if_needed(0), 0);
+    pointers.clear(1, 0);
+}
+
+#[test]
+fn reclaim_all_bounded_shares_budget() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, usize::MAX);
+    let hazardous = BoxMemory.allocate(0);
+    pointers.mark_ptr(1, 0, hazardous);
+    pointers.retire(0, hazardous);
+    for value in 1..8 {
+        pointers.retire(0, BoxMemory.allocate(value));
+    }
+    for value in 0..2 {
+        pointers.retire(1, BoxMemory.allocate(value));
+    }
+    assert_eq!(pointers.reclaim_all_bounded(0), ReclaimReport::default());
+
+    // Each thread checks half of the budget, so the second thread isn't starved by the first.
+    assert_eq!(pointers.reclaim_all_bounded(4), ReclaimReport { freed: 3, retained: 1 });
+    assert_eq!(pointers.pending(0), 7);
+    assert_eq!(pointers.pending(1), 0);
+
+    // The hazardous pointer is skipped by later calls instead of being checked again.
+    assert_eq!(pointers.reclaim_all_bounded(4), ReclaimReport { freed: 2, retained: 0 });
+    assert_eq!(pointers.reclaim_all_bounded(8), ReclaimReport { freed: 4, retained: 0 });
+    assert_eq!(pointers.pending(0), 1);
+    pointers.clear(1, 0);
+}
+
+#[test]
+fn retire_nonnull_reclaimed() {
+    let memory = Counting::default();
+    let pointers = Pointers::new(&memory, 1, 1, 8);
+    let pointer = NonNull::new(memory.allocate(17)).unwrap();
+    pointers.retire_nonnull(0, pointer);
+    assert!(pointers.contains_retired(pointer.as_ptr()));
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert_eq!(memory.live(), 0);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "retired a null pointer")]
+fn retire_null_panics() {
+    let pointers = Pointers::<u64, _>::new(BoxMemory, 1, 1, 8);
+    pointers.retire(0, ptr::null_mut());
+}
+
+#[test]
+fn protect_indexed_uses_free_domains() {
+    let pointers = Pointers::new(BoxMemory, 1, 2, 8);
+    let first = AtomicPtr::new(BoxMemory.allocate(17));
+    let second = AtomicPtr::new(BoxMemory.allocate(322));
+    let empty = AtomicPtr::new(ptr::null_mut());
+
+    // A null pointer leaves the domain unset so it is returned again.
+    assert_eq!(pointers.protect_indexed(0, &empty), Some((0, ptr::null_mut())));
+    assert_eq!(pointers.protect_indexed(0, &first), Some((0, first.load(Relaxed))));
+    assert_eq!(pointers.protect_indexed(0, &second), Some((1, second.load(Relaxed))));
+    assert_eq!(pointers.protect_indexed(0, &first), None);
+
+    pointers.clear(0, 0);
+    assert_eq!(pointers.protect_indexed(0, &second), Some((0, second.load(Relaxed))));
+    pointers.clear_all(0);
+    pointers.retire(0, first.into_inner());
+    pointers.retire(0, second.into_inner());
+}
+
+#[test]
+fn protect_optional_clears_on_null() {
+    let pointers = Pointers::new(BoxMemory, 1, 1, 8);
+    let value = BoxMemory.allocate(17);
+    let atomic = AtomicPtr::new(value);
+    assert_eq!(pointers.protect_optional(0, 0, &atomic), Some(value));
+    assert!(pointers.hazardous(value));
+
+    // Protecting a null pointer clears the hazardous pointer left by the previous protection.
+    atomic.store(ptr::null_mut(), Release);
+    assert_eq!(pointers.protect_optional(0, 0, &atomic), None);
+    assert!(!pointers.hazardous(value));
+    assert_eq!(pointers.hazards_of(0), [ptr::null_mut()]);
+    pointers.retire(0, value);
+}
+
+#[test]
+fn retire_if_checks_condition_first() {
+    let memory = Counting::default();
+    let pointers = Pointers::new(&memory, 1, 1, 8);
+    let value = memory.allocate(AtomicUsize::new(2));
+
+    // The condition may read the value, which is only retired once its count drops to zero.
+    let release = |p: *mut AtomicUsize| unsafe { (*p).fetch_sub(1, AcqRel) == 1 };
+    assert!(!pointers.retire_if(0, value, release));
+    assert_eq!(pointers.pending(0), 0);
+    assert!(pointers.retire_if(0, value, release));
+    assert_eq!(pointers.pending(0), 1);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert_eq!(memory.live(), 0);
+}
+
+#[test]
+fn observer_notified() {
+    let mut pointers = Pointers::new(BoxMemory, 2, 1, 3);
+    let events = Events::default();
+    pointers.set_observer(events.clone());
+
+    let hazardous = BoxMemory.allocate(0);
+    pointers.mark_ptr(0, 0, hazardous);
+    pointers.retire(1, hazardous);
+    pointers.retire(1, BoxMemory.allocate(1));
+    pointers.retire(1, BoxMemory.allocate(2));
+    pointers.reclaim(1);
+    pointers.clear(0, 0);
+    pointers.reclaim(1);
+
+    let expected = [
+        Event::Retired(1, 1),
+        Event::Retired(1, 2),
+        Event::Retired(1, 3),
+        Event::Reclaimed(1, ReclaimReport { freed: 2, retained: 1 }),
+        Event::Reclaimed(1, ReclaimReport { freed: 0, retained: 1 }),
+        Event::Reclaimed(1, ReclaimReport { freed: 1, retained: 0 }),
+    ];
+    assert_eq!(*events.0.borrow(), expected);
+}
+
+#[test]
+fn align_vec_fills_cacheline() {
+    #[cfg(target_arch="x86_64")]
+    if option_env!("HAZARD_CACHELINE_SIZE").is_none() {
+        assert_eq!(CACHELINE, 128);
+    }
+
+    assert_eq!(mem::size_of::<AlignVec<u8>>(), CACHELINE);
+    assert_eq!(mem::size_of::<AlignVec<String>>(), CACHELINE);
+    assert_eq!(mem::size_of::<AlignVec<[u64; 32]>>(), CACHELINE);
+    assert_eq!(mem::size_of::<AlignVec<AtomicPtr<u8>>>(), CACHELINE);
+}
+
+#[test]
+fn reclaimers_free_exactly_unprotected() {
+    fn check<R>(reclaimer: R) where R: for<'m> Reclaimer<usize, &'m Counting> + 'static {
+        let memory = Counting::default();
+        let pointers = PointersBuilder::new(&memory, 3, 2, usize::MAX)
+            .reclaimer(reclaimer)
+            .build();
+        let retired = (0..16).map(|v| memory.allocate(v)).collect::<Vec<_>>();
+        let marked = [1, 5, 6, 11, 15];
+        for (index, value) in marked.iter().enumerate() {
+            pointers.mark_ptr(index / 2, index % 2, retired[*value]);
+        }
+        for pointer in &retired {
+            pointers.retire(0, *pointer);
+        }
+
+        let report = pointers.reclaim(0);
+        assert_eq!(report, ReclaimReport { freed: 11, retained: 5 });
+        assert_eq!(memory.live(), 5);
+        let mut kept = pointers.drain(0).iter().map(|p| unsafe { **p }).collect::<Vec<_>>();
+        kept.sort();
+        assert_eq!(kept, marked);
+
+        for thread in 0..3 {
+            pointers.clear_all(thread);
+        }
+        for value in &marked {
+            pointers.retire(0, retired[*value]);
+        }
+        assert_eq!(pointers.reclaim(0).freed, 5);
+        assert_eq!(memory.live(), 0);
+    }
+
+    check(NaiveScan);
+    check(BatchScan);
+    check(SnapshotSet);
+    check(Sorted);
+    check(Bounded { max_checks: 16 });
+    check(DecisionScan::new(|_: *mut usize| ReclaimDecision::Free));
+}
+
+#[test]
+fn orphans_reclaimed_at_threshold() {
+    let pointers = Pointers::new(Counting::default(), 4, 1, 8);
+    let thread = pointers.register().unwrap();
+
+    // Retiring using a thread that was never registered orphans every pointer.
+    let unregistered = (thread + 1) % 4;
+    for value in 0..64 {
+        let pointer = pointers.memory().allocate(value);
+        pointers.retire(unregistered, pointer);
+    }
+    assert!(pointers.orphaned() < 8);
+    assert!(pointers.memory().live() < 8);
+
+    // Hazardous orphans survive the threshold reclamation.
+    let value = pointers.memory().allocate(64);
+    let atomic = AtomicPtr::new(value);
+    pointers.mark(thread, 0, &atomic);
+    pointers.retire(unregistered, value);
+    for value in 65..128 {
+        let pointer = pointers.memory().allocate(value);
+        pointers.retire(unregistered, pointer);
+    }
+    assert!(pointers.orphaned() >= 1);
+    assert_eq!(unsafe { *value }, 64);
+
+    pointers.clear(thread, 0);
+    pointers.reclaim_orphans();
+    assert_eq!(pointers.orphaned(), 0);
+    assert_eq!(pointers.memory().live(), 0);
+}
+
+#[test]
+fn orphans_retired_during_reclamation() {
+    let pointers = PointersBuilder::new(BoxMemory, 1, 1, 1)
+        .retired_layout(RetiredLayout::Global)
+        .build();
+    let chain = |next| BoxMemory.allocate(Chain { next, pointers: &pointers });
+
+    // Links retired by destructors during a reclamation are kept for the next one.
+    let last = chain(ptr::null_mut());
+    pointers.retire(0, chain(chain(last)));
+    assert_eq!(pointers.orphaned(), 1);
+    assert_eq!(pointers.reclaim_orphans().freed, 1);
+    assert_eq!(pointers.reclaim_orphans().freed, 1);
+    assert_eq!(pointers.orphaned(), 0);
+}
+
+#[test]
+fn layout_retired_with_memory() {
+    let pointers = Pointers::new(Counting::default(), 1, 1, 1024);
+
+    let (pointer, layout) = allocate_layout(pointers.memory(), "header");
+    pointers.retire_layout(0, pointer, layout);
+    pointers.retire(0, pointers.memory().allocate(String::from("value")));
+    assert_eq!(pointers.pending(0), 2);
+    assert_eq!(pointers.reclaim(0).freed, 2);
+    assert_eq!(pointers.memory().live(), 0);
+}
+
+#[test]
+fn layout_kept_when_drained() {
+    let pointers = Pointers::new(Counting::default(), 1, 1, 1024);
+
+    let (hazardous, layout) = allocate_layout(pointers.memory(), "hazardous");
+    let atomic = AtomicPtr::new(hazardous);
+    pointers.mark(0, 0, &atomic);
+    pointers.retire_layout(0, hazardous, layout);
+    let (pointer, layout) = allocate_layout(pointers.memory(), "reclaimable");
+    pointers.retire_layout(0, pointer, layout);
+    let value = pointers.memory().allocate(String::from("value"));
+    pointers.retire(0, value);
+
+    // Pointers retired with a layout are never returned for the caller to deallocate.
+    assert_eq!(pointers.drain_reclaimable(0), vec![value]);
+    unsafe { pointers.memory().deallocate(value); }
+    assert_eq!(pointers.pending(0), 2);
+    assert!(pointers.contains_retired(pointer));
+
+    assert_eq!(pointers.drain(0), vec![]);
+    assert_eq!(pointers.pending(0), 1);
+    assert_eq!(pointers.memory().live(), 1);
+    assert_eq!(unsafe { &*hazardous }, "hazardous");
+
+    pointers.clear(0, 0);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert!(pointers.is_quiescent());
+    assert_eq!(pointers.memory().live(), 0);
+}
+
+#[test]
+fn layout_forgotten_on_drop() {
+    let memory = Arc::new(Counting::default());
+
+    let pointers = Pointers::<String, _>::new(memory.clone(), 1, 1, 1024);
+    let (pointer, layout) = allocate_layout(&memory, "dropped");
+    pointers.retire_layout(0, pointer, layout);
+    drop(pointers);
+    assert_eq!(memory.live(), 0);
+
+    let pointers = Pointers::<String, _>::new(memory.clone(), 1, 1, 1024);
+    pointers.set_forget_on_drop(true);
+    let (pointer, layout) = allocate_layout(&memory, "forgotten");
+    pointers.retire_layout(0, pointer, layout);
+    drop(pointers);
+    assert_eq!(memory.live(), 1);
+    assert_eq!(unsafe { &*pointer }, "forgotten");
+
+    unsafe {
+        ptr::drop_in_place(pointer);
+        memory.deallocate_layout(pointer as *mut u8, layout);
+    }
+    assert_eq!(memory.live(), 0);
+}
+
+#[test]
+fn memory_shared_between_collections() {
+    let memory = Arc::new(Counting::default());
+    let strings = Pointers::<String, _>::new(memory.clone(), 1, 1, 4);
+    let integers = Pointers::<u64, _>::new(memory.clone(), 1, 1, 4);
+
+    for value in 0..16 {
+        strings.retire(0, memory.allocate(value.to_string()));
+        integers.retire(0, memory.allocate(value));
+    }
+    assert_eq!(memory.allocated.load(SeqCst), 32);
+
+    // Each collection deallocates its own pointers through the shared memory.
+    strings.reclaim_all();
+    assert_eq!(memory.live(), integers.pending(0));
+    integers.reclaim_all();
+    assert_eq!(memory.live(), 0);
+
+    drop(strings);
+    drop(integers);
+    assert_eq!(Arc::strong_count(&memory), 1);
+    assert_eq!(memory.deallocated.load(SeqCst), 32);
+}
+
+#[test]
+fn intrusive_retired_during_reclamation() {
+    let pointers = Pointers::new(BoxMemory, 1, 1, 1024);
+    let node = |child| BoxMemory.allocate(Node {
+        link: AtomicPtr::default(), child, pointers: &pointers,
+    });
+
+    let grandchild = node(ptr::null_mut());
+    let child = node(grandchild);
+    unsafe { pointers.retire_intrusive(0, node(child)); }
+
+    // Children retired by destructors during a reclamation are kept for the next one.
+    assert_eq!(pointers.reclaim_intrusive(0).freed, 1);
+    assert_eq!(pointers.reclaim_intrusive(0).freed, 1);
+    assert_eq!(pointers.reclaim_intrusive(0).freed, 1);
+    assert_eq!(pointers.reclaim_intrusive(0).freed, 0);
+}
+
+#[test]
+fn intrusive_reclaimed_when_quiescent() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 1024);
+    let node = |child| BoxMemory.allocate(Node {
+        link: AtomicPtr::default(), child, pointers: &pointers,
+    });
+
+    unsafe { pointers.retire_intrusive(1, node(node(ptr::null_mut()))); }
+    assert!(!pointers.is_quiescent());
+    assert!(pointers.wait_quiescent(None));
+}
+
+#[test]
+fn remote_and_deferred_accounted() {
+    let pointers = Pointers::new(BoxMemory, 2, 1, 1024);
+    let pointer = BoxMemory.allocate(17);
+    pointers.retire_remote(1, pointer);
+    assert_eq!(pointers.pending(1), 1);
+    assert!(!pointers.is_quiescent());
+    assert!(pointers.contains_retired(pointer));
+    assert!(pointers.wait_quiescent(None));
+    assert_eq!(pointers.pending(1), 0);
+}
+
+#[test]
+fn stack_retire_from_any_thread() {
+    let shared = Shared(PointersBuilder::new(BoxMemory, 2, 1, 16)
+        .retired_layout(RetiredLayout::Stack)
+        .build());
+
+    let atomic = AtomicPtr::new(BoxMemory.allocate(0));
+    shared.0.mark(1, 0, &atomic);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            let shared = &shared;
+            scope.spawn(move || {
+                for value in 0..1000 {
+                    shared.0.retire(0, BoxMemory.allocate(value));
+                }
+            });
+        }
+    });
+    assert!(shared.0.pending(0) < 16 * 4);
+
+    // The hazardous pointer survives every sweep of the stack.
+    let value = atomic.load(Relaxed);
+    shared.0.retire(0, value);
+    assert!(shared.0.contains_retired(value));
+    shared.0.reclaim(0);
+    assert_eq!(shared.0.pending(0), 1);
+    shared.0.clear(1, 0);
+    assert_eq!(shared.0.reclaim(0).freed, 1);
+    assert!(shared.0.is_quiescent());
+}
+
+#[test]
+fn unregister_current_on_spawned_threads() {
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Value(&'static AtomicUsize);
+
+    impl Drop for Value {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, SeqCst);
+        }
+    }
+
+    let shared = Arc::new(Shared(Pointers::new(BoxMemory, 4, 1, 16)));
+    for _ in 0..8 {
+        let handles = (0..4).map(|_| {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                let thread = shared.0.register().unwrap();
+                let hazardous = BoxMemory.allocate(Value(&DROPPED));
+                shared.0.mark_ptr(thread, 0, hazardous);
+                shared.0.retire(thread, hazardous);
+                for _ in 0..10 {
+                    shared.0.retire(thread, BoxMemory.allocate(Value(&DROPPED)));
+                }
+                assert_eq!(shared.0.unregister_current(), Some(thread));
+                assert_eq!(shared.0.unregister_current(), None);
+            })
+        }).collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!((0..4).all(|t| !shared.0.registered(t)));
+    }
+
+    // Unregistering cleared each hazardous pointer before its thread's retired pointers were
+    // reclaimed, so nothing was orphaned.
+    assert_eq!(shared.0.orphaned(), 0);
+    assert_eq!(DROPPED.load(SeqCst), 8 * 4 * 11);
+}
+
+#[test]
+fn static_retire_from_any_thread() {
+    let pointers = StaticPointers::<usize, Counting, 2, 1, 4>::new(Counting::default());
+
+    // Retiring using one thread index from several threads fills the buffer concurrently.
+    let value = pointers.memory().allocate(0);
+    pointers.mark_ptr(1, 0, value);
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            let pointers = &pointers;
+            scope.spawn(move || {
+                for value in 1..1000 {
+                    pointers.retire(0, pointers.memory().allocate(value));
+                }
+            });
+        }
+    });
+    pointers.retire(0, value);
+    pointers.reclaim(0);
+    assert_eq!(pointers.pending(0), 1);
+    assert_eq!(unsafe { *value }, 0);
+
+    pointers.clear(1, 0);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert_eq!(pointers.memory().live(), 0);
+}
+
+#[test]
+fn remote_checked_against_own_domains() {
+    let pointers = PointersBuilder::new(Counting::default(), 2, 1, 1024)
+        .skip_own_domains(true)
+        .build();
+
+    // A pointer retired on behalf of a thread survives while that thread protects it.
+    let atomic = AtomicPtr::new(pointers.memory().allocate(17));
+    pointers.mark(1, 0, &atomic);
+    pointers.retire_remote(1, atomic.load(Relaxed));
+    pointers.reclaim(1);
+    pointers.reclaim_orphans();
+    assert_eq!(pointers.memory().live(), 1);
+    assert_eq!(unsafe { *atomic.load(Relaxed) }, 17);
+
+    pointers.clear(1, 0);
+    pointers.reclaim_orphans();
+    assert_eq!(pointers.memory().live(), 0);
+}
+
+#[cfg(feature="async")]
+#[test]
+fn quiescence_waits_for_timer() {
+    let pointers = Pointers::new(BoxMemory, 1, 1, 1024);
+    let atomic = AtomicPtr::new(BoxMemory.allocate(17));
+    pointers.mark(0, 0, &atomic);
+    pointers.retire(0, atomic.load(Relaxed));
+
+    // Each timer is pending when it is first polled.
+    let timers = Cell::new(0);
+    let sleep = |_| {
+        timers.set(timers.get() + 1);
+        let mut elapsed = false;
+        future::poll_fn(move |_| {
+            if elapsed {
+                Poll::Ready(())
+            } else {
+                elapsed = true;
+                Poll::Pending
+            }
+        })
+    };
+
+    let interval = Duration::from_millis(1);
+    let mut future = pin!(pointers.reclaim_until_quiescent(interval, sleep));
+    let waker = Waker::from(Arc::new(Ignore));
+    let mut context = Context::from_waker(&waker);
+    assert_eq!(future.as_mut().poll(&mut context), Poll::Pending);
+    assert_eq!(future.as_mut().poll(&mut context), Poll::Pending);
+    assert_eq!(timers.get(), 2);
+
+    pointers.clear(0, 0);
+    assert_eq!(future.as_mut().poll(&mut context), Poll::Ready(()));
+    assert_eq!(timers.get(), 2);
+}
+
+#[test]
+fn aba_detected_on_slab_reuse() {
+    let pointers = Pointers::new(AbaGuard::new(SlabMemory::<u64>::new(1)), 1, 1, 1);
+    let memory = pointers.memory();
+
+    let first = memory.allocate(17u64);
+    let atomic = AtomicPtr::new(first);
+    let version = memory.version(atomic.load(Acquire));
+
+    // The value is replaced and reclaimed, and the slab reuses its block for the next value.
+    pointers.retire(0, atomic.swap(ptr::null_mut(), AcqRel));
+    assert_eq!(pointers.pending(0), 0);
+    let second = memory.allocate(322u64);
+    atomic.store(second, Release);
+    assert_eq!(atomic.load(Acquire), version.pointer());
+    assert!(!memory.is_current(version));
+
+    // A protected pointer can't be reused, so a version that is current stays current.
+    let version = memory.version(pointers.mark(0, 0, &atomic));
+    pointers.retire(0, atomic.swap(ptr::null_mut(), AcqRel));
+    assert!(memory.is_current(version));
+    assert_eq!(memory.try_allocate(42u64), Err(42));
+
+    pointers.clear(0, 0);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert!(!memory.is_current(version));
+    assert_eq!(memory.generation(second), 2);
+}
+
+#[test]
+fn slab_exhausted_and_recovered() {
+    let pointers = Pointers::new(SlabMemory::<u64>::new(4), 1, 1, 8);
+    let memory = pointers.memory();
+    assert_eq!(memory.try_allocate(17u128), Err(17));
+
+    // Every block is allocated and retired, but one value is still hazardous.
+    let values = (0..4).map(|i| memory.allocate(i as u64)).collect::<Vec<_>>();
+    assert_eq!(memory.try_allocate(4u64), Err(4));
+    pointers.mark_ptr(0, 0, values[0]);
+    for value in &values {
+        pointers.retire(0, *value);
+    }
+    assert_eq!(memory.available(), 0);
+
+    // Reclaiming makes every block but the hazardous one available again.
+    assert_eq!(pointers.reclaim(0), ReclaimReport { freed: 3, retained: 1 });
+    assert_eq!(memory.available(), 3);
+    let values = (0..3).map(|i| memory.try_allocate(i as u64).unwrap()).collect::<Vec<_>>();
+    assert_eq!(memory.try_allocate(3u64), Err(3));
+
+    pointers.clear(0, 0);
+    assert_eq!(pointers.reclaim(0).freed, 1);
+    assert_eq!(*unsafe { &*memory.allocate(3u64) }, 3);
+    for value in values {
+        pointers.retire(0, value);
+    }
+}
+
+#[test]
+#[should_panic(expected = "not interchangeable")]
+fn merge_distinct_slabs() {
+    let mut pointers = Pointers::new(Arc::new(SlabMemory::<u64>::new(4)), 1, 1, 8);
+    let slab = Arc::new(SlabMemory::<u64>::new(4));
+    let other = Pointers::new(slab.clone(), 1, 1, 8);
+    other.retire(0, slab.allocate(17));
+    pointers.merge(other);
+}
+
+#[test]
+fn exact_ignores_tagged_hazards() {
+    let retired = BoxMemory.allocate(17u64);
+    let tagged = (retired as usize | 1) as *mut u64;
+
+    let address = Pointers::<u64, BoxMemory, Address>::with_identity(BoxMemory, 1, 1, 8);
+    address.mark_ptr(0, 0, tagged);
+    assert!(address.hazardous(retired));
+    address.clear(0, 0);
+
+    let exact = Pointers::<u64, BoxMemory, Exact>::with_identity(BoxMemory, 1, 1, 8);
+    exact.mark_ptr(0, 0, tagged);
+    assert!(!exact.hazardous(retired));
+    exact.mark_ptr(0, 0, retired);
+    assert!(exact.hazardous(retired));
+    exact.retire(0, retired);
+    assert_eq!(exact.reclaim(0).retained, 1);
+    exact.clear(0, 0);
+    assert_eq!(exact.reclaim(0).freed, 1);
+}
+
+#[test]
+fn interior_protects_whole_value() {
+    let retired = BoxMemory.allocate([17u64, 322]);
+    let field = unsafe { (retired as *mut u64).add(1) } as *mut [u64; 2];
+    let past = unsafe { retired.add(1) };
+
+    let address = Pointers::<[u64; 2], BoxMemory, Address>::with_identity(BoxMemory, 1, 1, 8);
+    address.mark_ptr(0, 0, field);
+    assert!(!address.hazardous(retired));
+    address.clear(0, 0);
+
+    let interior = Pointers::<[u64; 2], BoxMemory, Interior>::with_identity(BoxMemory, 1, 1, 8);
+    interior.mark_ptr(0, 0, past);
+    assert!(!interior.hazardous(retired));
+    interior.mark_ptr(0, 0, field);
+    assert!(interior.hazardous(retired));
+    interior.retire(0, retired);
+    assert_eq!(interior.reclaim(0).retained, 1);
+    interior.clear(0, 0);
+    assert_eq!(interior.reclaim(0).freed, 1);
+}
@@ -0,0 +1,87 @@
+// Copyright 2017 Kyle Mayes
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate hazard;
+
+use std::sync::atomic::Ordering::*;
+
+use hazard::{AtomicPtr, Memory, Pointers};
+use hazard::stress::{self, CountingMemory, Node};
+
+//================================================
+// Functions
+//================================================
+
+/// Swaps the node using the odd threads and reads it using the even threads.
+fn read_or_swap(
+    thread: usize, pointers: &Pointers<Node, &CountingMemory>, pointer: &AtomicPtr<Node>
+) {
+    if thread % 2 == 0 {
+        let current = pointers.mark(thread, 0, pointer);
+        unsafe { (*current).value(); }
+        pointers.clear(thread, 0);
+    } else {
+        stress::swap(thread, pointers, pointer);
+    }
+}
+
+/// Swaps the node while it is protected and retires it with `retire_and_clear`.
+fn swap_and_clear(
+    thread: usize, pointers: &Pointers<Node, &CountingMemory>, pointer: &AtomicPtr<Node>
+) {
+    let memory = *pointers.memory();
+    let current = pointers.mark(thread, 0, pointer);
+    let new = memory.allocate(Node::new(unsafe { (*current).value() } + 1));
+    if pointer.compare_exchange(current, new, AcqRel, Acquire).is_ok() {
+        unsafe { (*current).value(); }
+        pointers.retire_and_clear(thread, 0, current);
+    } else {
+        pointers.clear(thread, 0);
+        unsafe { memory.deallocate(new); }
+    }
+}
+
+//================================================
+// Tests
+//================================================
+
+#[test]
+fn swap_threshold_one() {
+    let value = unsafe { stress::stress(4, 1, 1, 10_000, stress::swap) };
+    assert!(value <= 4 * 10_000);
+}
+
+#[test]
+fn swap_threshold_large() {
+    let value = unsafe { stress::stress(4, 1, 1_024, 10_000, stress::swap) };
+    assert!(value <= 4 * 10_000);
+}
+
+#[test]
+fn swap_many_threads() {
+    let value = unsafe { stress::stress(16, 2, 8, 2_000, stress::swap) };
+    assert!(value <= 16 * 2_000);
+}
+
+#[test]
+fn readers_and_writers() {
+    let value = unsafe { stress::stress(8, 1, 4, 10_000, read_or_swap) };
+    assert!(value <= 4 * 10_000);
+}
+
+#[test]
+fn retire_and_clear() {
+    let value = unsafe { stress::stress(4, 1, 2, 10_000, swap_and_clear) };
+    assert!(value <= 4 * 10_000);
+}